@@ -0,0 +1,156 @@
+use core::cell::{Cell, RefCell};
+
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Isolates panics of the inner animation, so they don't take down the whole controller.
+///
+/// With the `std` feature a panic while rendering a frame is caught and logged, the animation
+/// renders nothing from then on. Without `std` panics can't be caught and this is a pass-through.
+#[derive(Debug)]
+pub struct Catching<A> {
+    animation: A,
+    panicked: Cell<bool>,
+}
+
+impl<A> Catching<A> {
+    pub fn new(animation: A) -> Self {
+        Self {
+            animation,
+            panicked: Cell::new(false),
+        }
+    }
+
+    /// Returns `true` if the inner animation panicked.
+    pub fn has_panicked(&self) -> bool {
+        self.panicked.get()
+    }
+}
+
+impl<A, S> Animation<S> for Catching<A>
+where
+    S: Strip,
+    A: Animation<S>,
+{
+    #[cfg(feature = "std")]
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        use alloc::vec::Vec;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        if self.panicked.get() {
+            return Box::new(core::iter::empty());
+        }
+
+        // the frame is collected, so panics while iterating are caught as well
+        let frame = catch_unwind(AssertUnwindSafe(|| {
+            self.animation
+                .animate(animation_tick, strip, animation_meta)
+                .collect::<Vec<_>>()
+        }));
+
+        match frame {
+            Ok(frame) => Box::new(frame.into_iter()),
+            Err(_) => {
+                std::eprintln!(
+                    "animation {} panicked at tick {}, disabling it",
+                    self.animation.name().unwrap_or("<unnamed>"),
+                    animation_tick
+                );
+                self.panicked.set(true);
+                Box::new(core::iter::empty())
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        self.animation
+            .animate(animation_tick, strip, animation_meta)
+    }
+
+    fn duration(&self) -> Ticks {
+        self.animation.duration()
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        self.animation.name()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.animation.reset(strip)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use crate::{
+        animation::StaticAnimation,
+        color::{BlendMode, Color},
+        controller::{AnimationController, StartingPoint},
+        curve::Curve,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    struct Panicking;
+
+    impl<S: Strip> Animation<S> for Panicking {
+        fn animate(
+            &self,
+            _: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            panic!("broken animation");
+        }
+
+        fn duration(&self) -> Ticks {
+            5
+        }
+    }
+
+    #[test]
+    fn test_panicking_animation_is_isolated() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(Catching::new(Panicking), StartingPoint::Now)
+            .unwrap();
+        controller
+            .queue_animation(
+                StaticAnimation::new(
+                    1,
+                    0..6,
+                    HSVColor::red(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+
+        controller.update();
+        controller.update();
+
+        assert_eq!(strip.borrow().get_color_of_led(3), Color::red());
+    }
+}
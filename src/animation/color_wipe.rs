@@ -0,0 +1,139 @@
+use core::{cell::RefCell, fmt::Debug};
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    indexing::{Indexing, LedId},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Reveals a color led by led along the range.
+///
+/// Leds which are not revealed yet are left untouched. Without antialiasing the boundary led is
+/// revealed as a whole, with antialiasing its value is scaled by the fractional reveal position.
+#[derive(Debug)]
+pub struct ColorWipe<I> {
+    duration: Ticks,
+    range: I,
+    color: HSVColor,
+    antialiased: bool,
+}
+
+impl<I> ColorWipe<I> {
+    /// duration != 0
+    pub fn new(duration: Ticks, range: I, color: HSVColor) -> Self {
+        assert!(duration > 0, "duration must be at least 1");
+        Self {
+            duration,
+            range,
+            color,
+            antialiased: false,
+        }
+    }
+
+    /// Partially lights the boundary led for a smooth leading edge.
+    pub fn with_antialiasing(mut self, antialiased: bool) -> Self {
+        self.antialiased = antialiased;
+        self
+    }
+}
+
+impl<I, S> Animation<S> for ColorWipe<I>
+where
+    I: Indexing,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let time = (animation_tick as f32 / self.duration as f32).min(1.0);
+        let position = time * self.range.len() as f32;
+        let revealed = position as usize;
+        let fraction = position - revealed as f32;
+
+        let boundary_v = (self.color.v as f32 * fraction + 0.5) as u8;
+
+        let mut colorings = Vec::new();
+        for index in 0..self.range.len() {
+            let color = if index < revealed {
+                self.color
+            } else if index == revealed && self.antialiased && boundary_v > 0 {
+                HSVColor {
+                    v: boundary_v,
+                    ..self.color
+                }
+            } else {
+                break;
+            };
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mock::SPI, strip::mock::LedStrip};
+
+    use super::*;
+
+    type TestStrip = LedStrip<SPI, 5>;
+
+    fn frame(animation: &ColorWipe<core::ops::Range<u16>>, tick: Tick) -> Vec<(LedId, HSVColor)> {
+        let strip = Rc::new(RefCell::new(TestStrip::new()));
+        Animation::<TestStrip>::animate(
+            animation,
+            tick,
+            strip,
+            &AnimationMeta::new(crate::animation::IterationState::single()),
+        )
+        .map(|c| (c.led, c.color))
+        .collect()
+    }
+
+    #[test]
+    fn test_wipe_reveals_whole_leds() {
+        let color = HSVColor::new(0, 100, 100);
+        let animation = ColorWipe::new(10, 0..5, color);
+
+        assert!(frame(&animation, 0).is_empty());
+        assert_eq!(frame(&animation, 3), [(0, color)]);
+        assert_eq!(frame(&animation, 10).len(), 5);
+    }
+
+    #[test]
+    fn test_wipe_antialiased_boundary() {
+        let color = HSVColor::new(0, 100, 100);
+        let animation = ColorWipe::new(10, 0..5, color).with_antialiasing(true);
+
+        let state = frame(&animation, 3);
+        assert_eq!(state.len(), 2);
+        assert_eq!(state[0], (0, color));
+        let (led, boundary) = state[1];
+        assert_eq!(led, 1);
+        assert_eq!((boundary.h, boundary.s), (color.h, color.s));
+        assert!(
+            boundary.v.abs_diff(50) <= 1,
+            "{} should be about 50",
+            boundary.v
+        );
+
+        assert_eq!(frame(&animation, 4), [(0, color), (1, color)]);
+    }
+}
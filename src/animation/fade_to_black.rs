@@ -0,0 +1,53 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{
+    color::{BlendMode, HSVColor, LedColoring},
+    curve::Curve,
+    indexing::Indexing,
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta, StaticAnimation};
+
+/// Fades all leds of the range linearly from their current color to off.
+#[derive(Debug)]
+pub struct FadeToBlack<I>(StaticAnimation<I, HSVColor>);
+
+impl<I> FadeToBlack<I> {
+    /// duration != 0, min. 1
+    pub fn new(duration: Ticks, range: I) -> Self {
+        Self(StaticAnimation::new(
+            duration,
+            range,
+            HSVColor::new(0, 0, 0),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        ))
+    }
+}
+
+impl<S, I> Animation<S> for FadeToBlack<I>
+where
+    I: Indexing + Clone + 'static,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        self.0.animate(animation_tick, strip, animation_meta)
+    }
+
+    fn duration(&self) -> Ticks {
+        Animation::<S>::duration(&self.0)
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.0.reset(strip)
+    }
+}
@@ -0,0 +1,116 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta, IterationState};
+
+/// Plays a different animation depending on the iteration of a repeating timeline.
+///
+/// `intro` is played on the first (or only) iteration, `outro` on the last one and `main` on
+/// every iteration in between. Every iteration lasts as long as the longest of the three, a
+/// shorter animation holds its last frame.
+#[derive(Debug)]
+pub struct IntroOutro<A> {
+    intro: A,
+    main: A,
+    outro: A,
+}
+
+impl<A> IntroOutro<A> {
+    pub fn new(intro: A, main: A, outro: A) -> Self {
+        Self { intro, main, outro }
+    }
+}
+
+impl<A, S> Animation<S> for IntroOutro<A>
+where
+    S: Strip,
+    A: Animation<S>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let animation = match animation_meta.iteration_state {
+            IterationState::First { .. } | IterationState::Single => &self.intro,
+            IterationState::Looping { .. } => &self.main,
+            IterationState::Last { .. } => &self.outro,
+        };
+        animation.animate(
+            animation_tick.min(animation.duration()),
+            strip,
+            animation_meta,
+        )
+    }
+
+    fn duration(&self) -> Ticks {
+        self.intro
+            .duration()
+            .max(self.main.duration())
+            .max(self.outro.duration())
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.outro.reset(strip.clone());
+        self.main.reset(strip.clone());
+        self.intro.reset(strip);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mock::SPI, strip::mock::LedStrip};
+
+    use super::*;
+
+    type TestStrip = LedStrip<SPI, 1>;
+
+    struct Fixed(HSVColor, Ticks);
+
+    impl<S: Strip> Animation<S> for Fixed {
+        fn animate(
+            &self,
+            _: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            Box::new(core::iter::once(LedColoring::new(0, self.0)))
+        }
+
+        fn duration(&self) -> Ticks {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_intro_outro_follows_iteration_state() {
+        let intro = HSVColor::new(0, 100, 100);
+        let main = HSVColor::new(120, 100, 100);
+        let outro = HSVColor::new(240, 100, 100);
+        let animation = IntroOutro::new(Fixed(intro, 2), Fixed(main, 5), Fixed(outro, 3));
+        assert_eq!(Animation::<TestStrip>::duration(&animation), 5);
+
+        let strip = Rc::new(RefCell::new(TestStrip::new()));
+        let color_in = |iteration_index, remaining_iterations| {
+            let meta =
+                AnimationMeta::new(IterationState::new(iteration_index, remaining_iterations));
+            Animation::<TestStrip>::animate(&animation, 1, strip.clone(), &meta)
+                .next()
+                .unwrap()
+                .color
+        };
+
+        assert_eq!(color_in(0, 2), intro);
+        assert_eq!(color_in(1, 1), main);
+        assert_eq!(color_in(2, 0), outro);
+        assert_eq!(color_in(0, 0), intro);
+    }
+}
@@ -0,0 +1,150 @@
+use core::{cell::RefCell, ops::Add};
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta, BoxedAnimation};
+
+/// Renders `top` over `base`.
+///
+/// The frame of `base` is written to the strip before `top` is rendered, so `top` blends with it
+/// the same way it would with animations running in parallel on a timeline. A layer which is
+/// shorter than the other one holds its last frame.
+///
+/// Usually created with `+`, e.g. `base + top`.
+pub struct Layered<A, B> {
+    base: A,
+    top: B,
+}
+
+impl<A, B> Layered<A, B> {
+    pub fn new(base: A, top: B) -> Self {
+        Self { base, top }
+    }
+}
+
+impl<A, B, S> Animation<S> for Layered<A, B>
+where
+    S: Strip,
+    A: Animation<S>,
+    B: Animation<S>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let base_tick = animation_tick.min(self.base.duration());
+        let mut frame: Vec<_> = self
+            .base
+            .animate(base_tick, strip.clone(), animation_meta)
+            .collect();
+        {
+            let mut strip = strip.borrow_mut();
+            for coloring in frame.iter() {
+                strip.set_led_to_color(coloring.led, &coloring.color.into());
+            }
+        }
+
+        let top_tick = animation_tick.min(self.top.duration());
+        frame.extend(self.top.animate(top_tick, strip, animation_meta));
+        Box::new(frame.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        Animation::<S>::duration(&self.base).max(Animation::<S>::duration(&self.top))
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.top.reset(strip.clone());
+        self.base.reset(strip);
+    }
+}
+
+impl<A, B, C> Add<C> for Layered<A, B> {
+    type Output = Layered<Self, C>;
+
+    fn add(self, rhs: C) -> Self::Output {
+        Layered::new(self, rhs)
+    }
+}
+
+impl<S, C> Add<C> for BoxedAnimation<S>
+where
+    S: Strip,
+{
+    type Output = Layered<Self, C>;
+
+    fn add(self, rhs: C) -> Self::Output {
+        Layered::new(self, rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::StaticAnimation,
+        color::{BlendMode, Color},
+        controller::{AnimationController, StartingPoint},
+        curve::Curve,
+        indexing::LedId,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    type TestStrip = LedStrip<SPI, 6>;
+
+    struct MovingDot;
+
+    impl<S: Strip> Animation<S> for MovingDot {
+        fn animate(
+            &self,
+            animation_tick: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            let led = animation_tick as LedId;
+            Box::new(core::iter::once(LedColoring::new(led, HSVColor::blue())))
+        }
+
+        fn duration(&self) -> Ticks {
+            5
+        }
+    }
+
+    #[test]
+    fn test_solid_plus_moving_light() {
+        let solid: BoxedAnimation<TestStrip> = Box::new(StaticAnimation::new(
+            1,
+            0..6,
+            HSVColor::red(),
+            Curve::Step,
+            BlendMode::AllChannels,
+        ));
+        let layered = solid + MovingDot;
+        assert_eq!(Animation::<TestStrip>::duration(&layered), 5);
+
+        let strip = Rc::new(RefCell::new(TestStrip::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(layered, StartingPoint::Now)
+            .unwrap();
+        for _ in 0..3 {
+            controller.update();
+        }
+
+        let strip = strip.borrow();
+        for led in [0, 1, 3, 4, 5] {
+            assert_eq!(strip.get_color_of_led(led), Color::red());
+        }
+        assert_eq!(strip.get_color_of_led(2), Color::blue());
+    }
+}
@@ -0,0 +1,282 @@
+use core::cell::{Cell, RefCell};
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    indexing::{Indexing, LedId},
+    rng::Xoshiro128,
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+#[derive(Debug, Clone, Copy)]
+struct Comet {
+    start: u16,
+    age: u16,
+    lifetime: u16,
+}
+
+impl Comet {
+    fn head(&self) -> i32 {
+        i32::from(self.start) + i32::from(self.age)
+    }
+
+    fn fade(&self) -> f32 {
+        1.0 - f32::from(self.age) / f32::from(self.lifetime)
+    }
+}
+
+#[derive(Debug)]
+struct ShowerState {
+    rng: Xoshiro128,
+    comets: Vec<Option<Comet>>,
+    tick: Option<Tick>,
+}
+
+/// Spawns comets at random times and positions, which move along the range and fade out.
+///
+/// Comets are spawned from a seeded random generator, so the same seed always renders the same
+/// shower. At most `pool_size` comets are active at once and with `density` the probability to
+/// spawn a new comet per tick is specified (0.0 - 1.0).
+#[derive(Debug)]
+pub struct MeteorShower<I> {
+    duration: Ticks,
+    range: I,
+    color: HSVColor,
+    tail_len: u16,
+    density: f32,
+    seed: Cell<u64>,
+    state: RefCell<ShowerState>,
+}
+
+impl<I: Indexing> MeteorShower<I> {
+    /// tail_len != 0, pool_size != 0
+    pub fn new(
+        duration: Ticks,
+        range: I,
+        color: HSVColor,
+        tail_len: u16,
+        pool_size: usize,
+        density: f32,
+        seed: u64,
+    ) -> Self {
+        assert!(tail_len > 0, "tail length must be at least 1");
+        assert!(pool_size > 0, "pool size must be at least 1");
+        Self {
+            duration,
+            range,
+            color,
+            tail_len,
+            density,
+            seed: Cell::new(seed),
+            state: RefCell::new(ShowerState {
+                rng: Xoshiro128::new(seed),
+                comets: alloc::vec![None; pool_size],
+                tick: None,
+            }),
+        }
+    }
+
+    /// Amount of comets which were active at the last animated tick.
+    pub fn active_comets(&self) -> usize {
+        self.state.borrow().comets.iter().flatten().count()
+    }
+
+    /// Replaces the seed and restarts the shower, the next animated tick is simulated from the
+    /// beginning with the new seed.
+    pub fn reseed(&self, seed: u64) {
+        self.seed.set(seed);
+        self.reset(&mut self.state.borrow_mut());
+    }
+
+    fn reset(&self, state: &mut ShowerState) {
+        state.rng.seed(self.seed.get());
+        state.comets.iter_mut().for_each(|c| *c = None);
+        state.tick = None;
+    }
+
+    fn advance_to(&self, tick: Tick) {
+        let mut state = self.state.borrow_mut();
+        if state.tick.is_some_and(|t| t > tick) {
+            self.reset(&mut state);
+        }
+
+        while state.tick.map_or(true, |t| t < tick) {
+            self.step(&mut state);
+            state.tick = Some(state.tick.map_or(0, |t| t + 1));
+        }
+    }
+
+    fn step(&self, state: &mut ShowerState) {
+        for slot in state.comets.iter_mut() {
+            if let Some(comet) = slot {
+                comet.age += 1;
+                if comet.age >= comet.lifetime {
+                    *slot = None;
+                }
+            }
+        }
+
+        let len = self.range.len() as u32;
+        if len == 0 || state.rng.next_f32() >= self.density {
+            return;
+        }
+
+        let start = (state.rng.next_u32() % len) as u16;
+        let lifetime = u32::from(self.tail_len) + state.rng.next_u32() % len + 1;
+        let lifetime = u16::try_from(lifetime).unwrap_or(u16::MAX);
+        if let Some(slot) = state.comets.iter_mut().find(|c| c.is_none()) {
+            *slot = Some(Comet {
+                start,
+                age: 0,
+                lifetime,
+            });
+        }
+    }
+
+    fn value_at(&self, comets: &[Option<Comet>], led: i32) -> f32 {
+        comets
+            .iter()
+            .flatten()
+            .map(|comet| {
+                let distance = comet.head() - led;
+                if (0..i32::from(self.tail_len)).contains(&distance) {
+                    (1.0 - distance as f32 / f32::from(self.tail_len)) * comet.fade()
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+impl<S, I> Animation<S> for MeteorShower<I>
+where
+    I: Indexing,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        self.advance_to(animation_tick);
+
+        let state = self.state.borrow();
+        let mut colorings = Vec::new();
+        for index in 0..self.range.len() {
+            let value = self.value_at(&state.comets, index as i32);
+            let color = HSVColor {
+                v: (f32::from(self.color.v) * value) as u8,
+                ..self.color
+            };
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::cell::RefCell;
+
+    use alloc::{rc::Rc, vec::Vec};
+
+    use crate::{
+        animation::{AnimationMeta, IterationState},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    fn frame<S: Strip>(
+        shower: &MeteorShower<core::ops::Range<u16>>,
+        tick: Tick,
+        strip: Rc<RefCell<S>>,
+    ) -> Vec<HSVColor> {
+        shower
+            .animate(tick, strip, &AnimationMeta::new(IterationState::single()))
+            .map(|c| c.color)
+            .collect()
+    }
+
+    #[test]
+    fn test_meteor_shower_is_reproducible() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 30>::new()));
+        let a = MeteorShower::new(100, 0..30, HSVColor::new(200, 100, 100), 4, 3, 0.3, 99);
+        let b = MeteorShower::new(100, 0..30, HSVColor::new(200, 100, 100), 4, 3, 0.3, 99);
+
+        let frames = (0..100)
+            .map(|tick| frame(&a, tick, strip.clone()))
+            .collect::<Vec<_>>();
+        for (tick, expected) in frames.iter().enumerate() {
+            assert_eq!(&frame(&b, tick as Tick, strip.clone()), expected);
+        }
+        assert!(frames.iter().flatten().any(|c| c.v > 0));
+
+        // jumping back in time replays the same shower
+        assert_eq!(frame(&a, 42, strip.clone()), frames[42]);
+    }
+
+    #[test]
+    fn test_meteor_shower_reseed() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 30>::new()));
+        let shower = MeteorShower::new(100, 0..30, HSVColor::new(200, 100, 100), 4, 3, 0.3, 99);
+        let other = MeteorShower::new(100, 0..30, HSVColor::new(200, 100, 100), 4, 3, 0.3, 7);
+
+        let frames = (0..50)
+            .map(|tick| frame(&shower, tick, strip.clone()))
+            .collect::<Vec<_>>();
+
+        shower.reseed(7);
+        let reseeded = (0..50)
+            .map(|tick| frame(&shower, tick, strip.clone()))
+            .collect::<Vec<_>>();
+        for (tick, expected) in reseeded.iter().enumerate() {
+            assert_eq!(&frame(&other, tick as Tick, strip.clone()), expected);
+        }
+        assert_ne!(reseeded, frames);
+
+        shower.reseed(99);
+        assert_eq!(frame(&shower, 49, strip.clone()), frames[49]);
+    }
+
+    #[test]
+    fn test_meteor_shower_long_tail() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 30>::new()));
+        let shower = MeteorShower::new(20, 0..30, HSVColor::new(0, 100, 100), u16::MAX, 2, 1.0, 5);
+
+        for tick in 0..20 {
+            assert_eq!(frame(&shower, tick, strip.clone()).len(), 30);
+        }
+        assert!(shower.active_comets() > 0);
+    }
+
+    #[test]
+    fn test_meteor_shower_respects_pool_size() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 30>::new()));
+        let shower = MeteorShower::new(200, 0..30, HSVColor::new(0, 100, 100), 5, 2, 1.0, 5);
+
+        let mut max_active = 0;
+        for tick in 0..200 {
+            let frame = frame(&shower, tick, strip.clone());
+            assert_eq!(frame.len(), 30);
+            max_active = max_active.max(shower.active_comets());
+        }
+        assert_eq!(max_active, 2);
+    }
+}
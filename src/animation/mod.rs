@@ -9,11 +9,39 @@ use crate::{
 
 use super::timeline::{Tick, Ticks};
 
+mod catching;
+mod color_wipe;
+mod fade_to_black;
+mod intro_outro;
+mod layered;
+mod meteor_shower;
+mod morph_animation;
+mod path_animation;
+mod progress_bar;
+mod rainbow_cycle;
 mod running_light;
+mod spinner;
 mod static_animation;
+mod static_animation_2d;
+mod then;
+mod tick_divider;
 
+pub use catching::Catching;
+pub use color_wipe::ColorWipe;
+pub use fade_to_black::FadeToBlack;
+pub use intro_outro::IntroOutro;
+pub use layered::Layered;
+pub use meteor_shower::MeteorShower;
+pub use morph_animation::MorphAnimation;
+pub use path_animation::PathAnimation;
+pub use progress_bar::ProgressBar;
+pub use rainbow_cycle::RainbowCycle;
 pub use running_light::{AnimationLen, RunningLight};
+pub use spinner::Spinner;
 pub use static_animation::StaticAnimation;
+pub use static_animation_2d::StaticAnimation2D;
+pub use then::Then;
+pub use tick_divider::TickDivider;
 
 #[cfg(test)]
 mod testing;
@@ -77,6 +105,15 @@ where
     ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>>;
 
     fn duration(&self) -> Ticks;
+
+    /// Human readable name of the animation, used for debugging.
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Writes the colors the animation cached from the strip back and forgets them, so the next
+    /// [animate](Self::animate) starts over from the strip, e.g. after stepping back a tick.
+    fn reset(&self, _strip: Rc<RefCell<S>>) {}
 }
 
 pub type BoxedAnimation<S> = Box<dyn Animation<S>>;
@@ -97,13 +134,74 @@ where
     fn duration(&self) -> Ticks {
         self.deref().duration()
     }
+
+    fn name(&self) -> Option<&'static str> {
+        self.deref().name()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.deref().reset(strip)
+    }
+}
+
+/// Attaches a human readable name to an animation.
+pub struct NamedAnimation<A> {
+    name: &'static str,
+    animation: A,
+}
+
+impl<A> NamedAnimation<A> {
+    pub fn new(name: &'static str, animation: A) -> Self {
+        Self { name, animation }
+    }
+}
+
+impl<A, S> Animation<S> for NamedAnimation<A>
+where
+    S: Strip,
+    A: Animation<S>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        self.animation
+            .animate(animation_tick, strip, animation_meta)
+    }
+
+    fn duration(&self) -> Ticks {
+        self.animation.duration()
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some(self.name)
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.animation.reset(strip)
+    }
+}
+
+impl<A> Debug for NamedAnimation<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NamedAnimation")
+            .field("name", &self.name)
+            .finish()
+    }
 }
 
 pub trait TimedAt {
     fn at_tick(&self) -> Tick;
 }
 
-pub trait TimedAnimationAt<S: Strip>: TimedAt + Animation<S> {}
+pub trait TimedAnimationAt<S: Strip>: TimedAt + Animation<S> {
+    /// Tick at which the animation has finished, `at_tick() + duration()`.
+    fn max_tick(&self) -> Tick {
+        self.at_tick() + self.duration()
+    }
+}
 
 impl<S, T> TimedAnimationAt<S> for T
 where
@@ -147,6 +245,14 @@ where
     fn duration(&self) -> Ticks {
         self.1.duration()
     }
+
+    fn name(&self) -> Option<&'static str> {
+        self.1.name()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.1.reset(strip)
+    }
 }
 
 impl<A, S> TimedAt for TimedAnimation<A, S> {
@@ -164,6 +270,7 @@ where
         f.debug_tuple("TimedAnimation")
             .field(&self.0)
             .field(&self.1.duration())
+            .field(&self.1.name())
             .finish()
     }
 }
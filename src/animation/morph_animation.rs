@@ -0,0 +1,150 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{blend_colors, BlendMode, HSVColor, LedColoring},
+    color_cache::ColorCache,
+    indexing::{Indexing, LedId},
+    pattern::{MorphPattern, Pattern, Progress},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Morphs from pattern `a` to pattern `b` over the duration of the animation.
+///
+/// The patterns are stretched over the whole range and blended onto the colors of the strip at
+/// the first animated tick.
+#[derive(Debug)]
+pub struct MorphAnimation<I, A, B> {
+    duration: Ticks,
+    range: I,
+    a: A,
+    b: B,
+    blend_mode: BlendMode,
+    start_colors: RefCell<ColorCache>,
+}
+
+impl<I, A, B> MorphAnimation<I, A, B> {
+    /// duration != 0
+    pub fn new(duration: Ticks, range: I, a: A, b: B, blend_mode: BlendMode) -> Self {
+        assert!(duration > 0, "duration must be at least 1");
+        Self {
+            duration,
+            range,
+            a,
+            b,
+            blend_mode,
+            start_colors: RefCell::new(ColorCache::new()),
+        }
+    }
+}
+
+impl<I: Indexing, A, B> MorphAnimation<I, A, B> {
+    fn cache_start_colors<S: Strip>(&self, strip: &S) {
+        let mut start_colors = self.start_colors.borrow_mut();
+        for index in 0..self.range.len() {
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            for led in leds {
+                let _ = start_colors.cache_color(led, &strip.get_color_of_led(led).into());
+            }
+        }
+    }
+}
+
+impl<S, I, A, B> Animation<S> for MorphAnimation<I, A, B>
+where
+    S: Strip,
+    I: Indexing,
+    A: Pattern<Color = HSVColor> + Clone,
+    B: Pattern<Color = HSVColor> + Clone,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let t = (animation_tick as f32 / self.duration as f32).min(1.0);
+        let pattern = MorphPattern::new(self.a.clone(), self.b.clone(), t);
+        let len = u16::try_from(self.range.len()).unwrap();
+        let strip = strip.borrow();
+        if self.start_colors.borrow().cache_size() == 0 {
+            self.cache_start_colors(&*strip);
+        }
+        let start_colors = self.start_colors.borrow();
+
+        let mut colorings = Vec::new();
+        for index in 0..len {
+            let color = pattern.color_at(Progress::new(index, len));
+            let leds = self
+                .range
+                .index(LedId::from(index))
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| {
+                let from = start_colors
+                    .load_color(led)
+                    .unwrap_or_else(|| strip.get_color_of_led(led).into());
+                LedColoring::new(led, blend_colors(from, color, self.blend_mode))
+            }));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.start_colors
+            .borrow_mut()
+            .restore_to(&mut *strip.borrow_mut());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::IterationState, color::TransparentColor, mock::SPI, strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_morph_animation_from_start_colors() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 3>::new()));
+        let red = HSVColor::new(0, 100, 100);
+        let animation = MorphAnimation::new(
+            4,
+            0..3,
+            TransparentColor::new(red, 1.0),
+            TransparentColor::opaque(red),
+            BlendMode::AllChannels,
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+
+        let frames = (0..=4)
+            .map(|tick| {
+                let frame = animation
+                    .animate(tick, strip.clone(), &meta)
+                    .collect::<Vec<_>>();
+                for coloring in frame.iter() {
+                    strip
+                        .borrow_mut()
+                        .set_led_to_color(coloring.led, &coloring.color.into());
+                }
+                frame.iter().map(|c| c.color).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(frames[0], [HSVColor::new(0, 0, 0); 3]);
+        assert_eq!(frames[2], [HSVColor::new(0, 50, 50); 3]);
+        assert_eq!(frames[4], [red; 3]);
+    }
+}
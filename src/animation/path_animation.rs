@@ -0,0 +1,142 @@
+use core::{cell::RefCell, fmt::Debug};
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    indexing::{Indexing, LedId},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+type PathFn = Box<dyn Fn(f32) -> f32>;
+
+/// Moves a single light along a caller provided path.
+///
+/// The path maps the normalized time (0.0 - 1.0) of the animation to a normalized position
+/// (0.0 - 1.0) on the range. The led at the position is lit with the color, all other leds of the
+/// range are switched off.
+pub struct PathAnimation<I> {
+    duration: Ticks,
+    range: I,
+    color: HSVColor,
+    path: PathFn,
+}
+
+impl<I> PathAnimation<I> {
+    /// duration != 0
+    pub fn new<F>(duration: Ticks, range: I, color: HSVColor, path: F) -> Self
+    where
+        F: Fn(f32) -> f32 + 'static,
+    {
+        assert!(duration > 0, "duration must be at least 1");
+        Self {
+            duration,
+            range,
+            color,
+            path: Box::new(path),
+        }
+    }
+}
+
+impl<I: Indexing> PathAnimation<I> {
+    fn position_at(&self, animation_tick: Tick) -> usize {
+        let time = (animation_tick as f32 / self.duration as f32).min(1.0);
+        let position = (self.path)(time).clamp(0.0, 1.0);
+        let last_index = self.range.len().saturating_sub(1);
+        (position * last_index as f32 + 0.5) as usize
+    }
+}
+
+impl<I, S> Animation<S> for PathAnimation<I>
+where
+    I: Indexing,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let position = self.position_at(animation_tick);
+
+        let mut colorings = Vec::new();
+        for index in 0..self.range.len() {
+            let color = if index == position {
+                self.color
+            } else {
+                HSVColor::default()
+            };
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+}
+
+impl<I: Debug> Debug for PathAnimation<I> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PathAnimation")
+            .field("duration", &self.duration)
+            .field("range", &self.range)
+            .field("color", &self.color)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::testing::{AnimationTester, Iterations},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    fn lit_at(position: LedId, color: HSVColor) -> impl Iterator<Item = (LedId, HSVColor)> {
+        (0..5).map(move |led| {
+            if led == position {
+                (led, color)
+            } else {
+                (led, HSVColor::default())
+            }
+        })
+    }
+
+    #[test]
+    fn test_path_animation_follows_path() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 5>::new()));
+        let color = HSVColor::new(0, 100, 100);
+        let animation = PathAnimation::new(10, 0..5, color, |t| 1.0 - t);
+
+        let mut tester = AnimationTester::new(animation, Iterations::Single, strip);
+
+        tester.assert_state(0, lit_at(4, color));
+        tester.assert_state(5, lit_at(2, color));
+        tester.assert_state(10, lit_at(0, color));
+    }
+
+    #[test]
+    fn test_path_animation_clamps_position() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 5>::new()));
+        let color = HSVColor::new(0, 100, 100);
+        let animation = PathAnimation::new(10, 0..5, color, |t| t * 3.0 - 1.0);
+
+        let mut tester = AnimationTester::new(animation, Iterations::Single, strip);
+
+        tester.assert_state(0, lit_at(0, color));
+        tester.assert_state(10, lit_at(4, color));
+    }
+}
@@ -0,0 +1,127 @@
+use core::cell::{Cell, RefCell};
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring, Spectrum},
+    indexing::{Indexing, LedId},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+    util::clamp_percentage,
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Shows a shared value (0.0 - 1.0) as filled part of the range.
+///
+/// The first `round(value * len)` leds are colored with the spectrum, sampled over the whole
+/// range, the remaining leds show the background. The value is read every frame, so it can be
+/// changed while the animation runs.
+pub struct ProgressBar<I, SP> {
+    duration: Ticks,
+    range: I,
+    spectrum: SP,
+    background: HSVColor,
+    value: Rc<Cell<f32>>,
+}
+
+impl<I, SP> ProgressBar<I, SP> {
+    pub fn new(
+        duration: Ticks,
+        range: I,
+        spectrum: SP,
+        background: HSVColor,
+        value: Rc<Cell<f32>>,
+    ) -> Self {
+        Self {
+            duration,
+            range,
+            spectrum,
+            background,
+            value,
+        }
+    }
+}
+
+impl<I, SP> ProgressBar<I, SP>
+where
+    I: Indexing,
+{
+    fn filled_len(&self) -> usize {
+        (clamp_percentage(self.value.get()) * self.range.len() as f32).round() as usize
+    }
+}
+
+impl<I, SP, S> Animation<S> for ProgressBar<I, SP>
+where
+    I: Indexing,
+    SP: Spectrum<Color = HSVColor>,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        _: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let len = self.range.len();
+        let filled_len = self.filled_len();
+
+        let mut colorings = Vec::new();
+        for index in 0..len {
+            let color = if index < filled_len {
+                let percentage = clamp_percentage(index as f32 / (len as f32 - 1.0));
+                self.spectrum.color_at(percentage).color
+            } else {
+                self.background
+            };
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::testing::{AnimationTester, Iterations},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_progress_bar_follows_value() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 5>::new()));
+        let fill = HSVColor::new(120, 100, 100);
+        let background = HSVColor::new(0, 0, 5);
+        let value = Rc::new(Cell::new(0.4));
+        let animation = ProgressBar::new(100, 0..5, fill, background, value.clone());
+
+        let mut tester = AnimationTester::new(animation, Iterations::Single, strip);
+        let state = |filled: LedId| {
+            (0..5).map(move |led| (led, if led < filled { fill } else { background }))
+        };
+
+        tester.assert_state(0, state(2));
+
+        value.set(0.9);
+        tester.assert_state(1, state(5));
+
+        value.set(0.0);
+        tester.assert_state(2, state(0));
+
+        value.set(1.7);
+        tester.assert_state(3, state(5));
+    }
+}
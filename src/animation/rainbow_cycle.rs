@@ -0,0 +1,105 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    indexing::{Indexing, LedId},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Rainbow spanning the range, which cycles once around the color wheel per duration.
+///
+/// With `spatial_freq` it is specified how many rainbows fit on the range, the led at index `i`
+/// has the hue `(i * 360 / len * spatial_freq + tick * 360 / duration) % 360`.
+#[derive(Debug)]
+pub struct RainbowCycle<I> {
+    duration: Ticks,
+    range: I,
+    spatial_freq: f32,
+}
+
+impl<I> RainbowCycle<I> {
+    /// duration != 0
+    pub fn new(duration: Ticks, range: I, spatial_freq: f32) -> Self {
+        assert!(duration > 0, "duration must be at least 1");
+        Self {
+            duration,
+            range,
+            spatial_freq,
+        }
+    }
+}
+
+impl<I, S> Animation<S> for RainbowCycle<I>
+where
+    I: Indexing,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let len = self.range.len() as f32;
+        let time_offset = animation_tick as f32 * 360.0 / self.duration as f32;
+
+        let mut colorings = Vec::new();
+        for index in 0..self.range.len() {
+            let spatial_offset = index as f32 * 360.0 / len * self.spatial_freq;
+            let h = ((spatial_offset + time_offset) % 360.0) as u16;
+            let color = HSVColor::new(h, 100, 100);
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::testing::{AnimationTester, Iterations},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    fn rainbow(h: [u16; 4]) -> [(LedId, HSVColor); 4] {
+        [0, 1, 2, 3].map(|led| (led, HSVColor::new(h[led as usize], 100, 100)))
+    }
+
+    #[test]
+    fn test_rainbow_cycle() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let mut tester =
+            AnimationTester::new(RainbowCycle::new(8, 0..4, 1.0), Iterations::Single, strip);
+
+        tester.assert_state(0, rainbow([0, 90, 180, 270]));
+        tester.assert_state(2, rainbow([90, 180, 270, 0]));
+        tester.assert_state(8, rainbow([0, 90, 180, 270]));
+    }
+
+    #[test]
+    fn test_rainbow_cycle_spatial_freq() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let mut tester =
+            AnimationTester::new(RainbowCycle::new(8, 0..4, 2.0), Iterations::Single, strip);
+
+        tester.assert_state(0, rainbow([0, 180, 0, 180]));
+        tester.assert_state(1, rainbow([45, 225, 45, 225]));
+    }
+}
@@ -5,10 +5,12 @@ use alloc::{boxed::Box, rc::Rc};
 use crate::{
     color::{blend_colors, BlendMode, HSVColor, LedColoring},
     color_cache::ColorCache,
+    curve::{calculate_with_curve_percentage, Curve},
     indexing::{Index, Indexing, LedId},
     pattern::{Pattern, Progress},
     strip::Strip,
     timeline::{Tick, Ticks},
+    util::saturating_u16,
 };
 
 use super::{Animation, AnimationMeta};
@@ -37,6 +39,8 @@ pub struct RunningLight<I, P> {
     border_wrapping: BorderType,
     fade_cache: Option<Rc<RefCell<ColorCache>>>,
     blend_mode: BlendMode,
+    transition_curve: Option<Curve>,
+    paint: bool,
 }
 
 impl<I, P: Pattern> RunningLight<I, P> {
@@ -64,8 +68,24 @@ impl<I, P: Pattern> RunningLight<I, P> {
                 false => BorderType::ClosedStartEnd,
             },
             blend_mode,
+            transition_curve: None,
+            paint: false,
         }
     }
+
+    /// Eases the blend from the previous color of a led to the pattern color with `curve`,
+    /// instead of following the transparency of the pattern linearly.
+    pub fn with_transition_curve(mut self, curve: Curve) -> Self {
+        self.transition_curve = Some(curve);
+        self
+    }
+
+    /// With `paint` the leds keep the color the light left behind, instead of being restored to
+    /// their previous color after the light passed.
+    pub fn with_paint(mut self, paint: bool) -> Self {
+        self.paint = paint;
+        self
+    }
 }
 
 impl<S, I, P> Animation<S> for RunningLight<I, P>
@@ -81,9 +101,13 @@ where
         _: &AnimationMeta,
     ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
         let animation_len = match self.len {
-            AnimationLen::FullStretch => self.range.len() as u16,
+            AnimationLen::FullStretch => saturating_u16(self.range.len()),
             AnimationLen::Static(len) => len,
         };
+        if self.range.len() == 0 || animation_len == 0 {
+            return Box::new(core::iter::empty());
+        }
+
         let jumps = calc_animation_jumps(&self.range, animation_len, self.border_wrapping);
         let act_jump = scale_time_to_jump(animation_tick, self.duration, jumps, self.start_offset);
         let start_led_id = scale_jump_to_animation_start(animation_len, act_jump);
@@ -91,7 +115,7 @@ where
         let animation_iter = ActiveRangeIter::new(
             start_led_id,
             animation_len,
-            u16::try_from(self.range.len()).unwrap(),
+            saturating_u16(self.range.len()),
             self.border_wrapping,
         );
 
@@ -104,6 +128,8 @@ where
                 animation_len,
                 self.fade_cache.clone(),
                 self.blend_mode,
+                self.transition_curve.clone(),
+                self.paint,
             )
             .flatten(),
         )
@@ -112,29 +138,44 @@ where
     fn duration(&self) -> Ticks {
         self.duration
     }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        if let Some(cache) = self.fade_cache.as_ref() {
+            cache.borrow_mut().restore_to(&mut *strip.borrow_mut());
+        }
+    }
 }
 
-fn calc_animation_jumps<I: Indexing>(range: &I, animation_len: u16, border: BorderType) -> u16 {
-    let led_range_len = range.len() as u16;
+/// Calculated in `u32`, so neither a single led window nor long ranges with long windows
+/// overflow.
+fn calc_animation_jumps<I: Indexing>(range: &I, animation_len: u16, border: BorderType) -> u32 {
+    let led_range_len = u32::from(saturating_u16(range.len()));
     match border {
-        BorderType::ClosedStartEnd => led_range_len + (animation_len - 2),
-        BorderType::WrappingStartEnd => led_range_len - 1,
+        BorderType::ClosedStartEnd => (led_range_len + u32::from(animation_len)).saturating_sub(2),
+        BorderType::WrappingStartEnd => led_range_len.saturating_sub(1),
         // _ => unimplemented!(),
     }
 }
 
-fn scale_time_to_jump(time: Tick, duration: Ticks, jumps: u16, start_offset: i16) -> u16 {
-    let jump = (time as f32 / (duration as f32 / jumps as f32)) as i16 + start_offset;
-    if jump > jumps as i16 {
-        jump.unsigned_abs() % jumps
-    } else if jump < 0 {
-        jumps - jump.unsigned_abs()
-    } else {
-        jump as u16
+/// Calculated with integers in `u64`/`i64`, so neither long durations nor many jumps truncate.
+fn scale_time_to_jump(time: Tick, duration: Ticks, jumps: u32, start_offset: i16) -> u32 {
+    if jumps == 0 {
+        return 0;
     }
+    let jumps = i64::from(jumps);
+    let jump = i64::try_from(u64::from(time) * jumps.unsigned_abs() / u64::from(duration.max(1)))
+        .unwrap()
+        + i64::from(start_offset);
+    let jump = if jump > jumps || jump < 0 {
+        jump.rem_euclid(jumps)
+    } else {
+        jump
+    };
+    // 0 <= jump <= jumps, so it always fits
+    u32::try_from(jump).unwrap()
 }
 
-fn scale_jump_to_animation_start(animation_len: u16, act_jump: u16) -> i32 {
+fn scale_jump_to_animation_start(animation_len: u16, act_jump: u32) -> i32 {
     0 - (animation_len - 1) as i32 + act_jump as i32
 }
 
@@ -163,6 +204,8 @@ pub struct CurveBatchIterator<I, S, P> {
     led_controller: Rc<RefCell<S>>,
     fade_cache: Option<Rc<RefCell<ColorCache>>>,
     blend_mode: BlendMode,
+    transition_curve: Option<Curve>,
+    paint: bool,
 }
 
 impl<I, S, P> CurveBatchIterator<I, S, P> {
@@ -174,6 +217,8 @@ impl<I, S, P> CurveBatchIterator<I, S, P> {
         animation_len: u16,
         fade_cache: Option<Rc<RefCell<ColorCache>>>,
         blend_mode: BlendMode,
+        transition_curve: Option<Curve>,
+        paint: bool,
     ) -> Self {
         Self {
             index: 0,
@@ -186,6 +231,8 @@ impl<I, S, P> CurveBatchIterator<I, S, P> {
             animation_part_item_idx: None,
             fade_cache,
             blend_mode,
+            transition_curve,
+            paint,
         }
     }
 }
@@ -251,6 +298,8 @@ where
             pattern: self.pattern.clone(),
             animation_len: self.animation_len.clone(),
             blend_mode: self.blend_mode.clone(),
+            transition_curve: self.transition_curve.clone(),
+            paint: self.paint,
         };
         self.index += 1;
 
@@ -266,6 +315,8 @@ pub struct FadeIter<I, S, P> {
     pattern: P,
     animation_len: u16,
     blend_mode: BlendMode,
+    transition_curve: Option<Curve>,
+    paint: bool,
 }
 
 impl<I, S, P> FadeIter<I, S, P>
@@ -286,13 +337,22 @@ where
 
     fn map_led_idx_to_color(&self, general_idx: LedId, idx: LedId) -> HSVColor {
         if let Some(cache) = self.fade_cache.as_ref() {
-            self.cache_led_color(cache, general_idx);
+            // a painted led is blended from its painted color, not from the strip
+            let painted = self.paint && cache.borrow().load_color(general_idx).is_some();
+            if !painted {
+                self.cache_led_color(cache, general_idx);
+            }
         }
 
-        let animation_color = self.pattern.color_at(Progress::new(
+        let mut animation_color = self.pattern.color_at(Progress::new(
             self.animation_len - idx - 1,
             self.animation_len,
         ));
+        if let Some(curve) = self.transition_curve.as_ref() {
+            let opacity = 1.0 - animation_color.transparency;
+            let opacity = calculate_with_curve_percentage(curve, &0f32, &1f32, opacity);
+            animation_color.transparency = 1.0 - opacity;
+        }
 
         let from_color = self
             .fade_cache
@@ -305,8 +365,12 @@ where
         };
 
         if let Some(cache) = self.fade_cache.as_ref() {
-            if idx == 0 {
-                let _ = cache.borrow_mut().remove_cache(general_idx);
+            let mut cache = cache.borrow_mut();
+            if self.paint {
+                let _ = cache.remove_cache(general_idx);
+                let _ = cache.cache_color(general_idx, &new_color);
+            } else if idx == 0 {
+                let _ = cache.remove_cache(general_idx);
             }
         }
 
@@ -466,6 +530,7 @@ mod test {
 
     use crate::{
         animation::testing::{AnimationTester, Iterations},
+        animation::IterationState,
         color::{HSVColor, TransparentColor},
         curve::{calculate_with_curve, Curve},
         mock::SPI,
@@ -527,6 +592,70 @@ mod test {
         assert_matches!(iter.next(), None);
     }
 
+    #[test]
+    fn test_scale_time_to_jump_long_duration() {
+        let duration = 100_000;
+
+        assert_eq!(scale_time_to_jump(0, duration, 30, 0), 0);
+        assert_eq!(scale_time_to_jump(50_000, duration, 30, 0), 15);
+        assert_eq!(scale_time_to_jump(99_999, duration, 30, 0), 29);
+        assert_eq!(scale_time_to_jump(100_000, duration, 30, 0), 30);
+        assert_eq!(scale_time_to_jump(150_000, duration, 30, 0), 15);
+        assert_eq!(scale_time_to_jump(0, duration, 30, -2), 28);
+
+        assert_eq!(scale_time_to_jump(90_000, duration, 40_000, 0), 36_000);
+        assert_eq!(scale_time_to_jump(99_999, duration, 65_535, 0), 65_534);
+    }
+
+    #[test]
+    fn test_calc_animation_jumps() {
+        let closed = BorderType::ClosedStartEnd;
+        let wrapping = BorderType::WrappingStartEnd;
+
+        assert_eq!(calc_animation_jumps(&(0u16..10), 1, closed), 9);
+        assert_eq!(calc_animation_jumps(&(0u16..10), 1, wrapping), 9);
+        assert_eq!(calc_animation_jumps(&(0u16..1), 1, closed), 0);
+        assert_eq!(calc_animation_jumps(&(0u16..10), 5, closed), 13);
+
+        assert_eq!(
+            calc_animation_jumps(&(0u16..u16::MAX), u16::MAX, closed),
+            2 * u32::from(u16::MAX) - 2
+        );
+        assert_eq!(scale_time_to_jump(5, 10, 0, 3), 0);
+        assert_eq!(scale_time_to_jump(50, 100, 131_068, 0), 65_534);
+    }
+
+    #[test]
+    fn test_running_light_single_led_window() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 20>::new()));
+        let pattern = HillPattern::new(
+            1,
+            TransparentColor::opaque(HSVColor::new(100, 100, 100)),
+            Curve::Linear,
+        );
+        let animation = RunningLight::new(
+            30,
+            6u16..10,
+            pattern,
+            AnimationLen::Static(1),
+            0,
+            false,
+            BlendMode::AllChannels,
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+
+        let leds = |tick| {
+            animation
+                .animate(tick, led_controller.clone(), &meta)
+                .map(|c| c.led)
+                .collect::<alloc::vec::Vec<_>>()
+        };
+        assert_eq!(leds(0), [6]);
+        assert_eq!(leds(10), [7]);
+        assert_eq!(leds(20), [8]);
+        assert_eq!(leds(30), [9]);
+    }
+
     #[test]
     fn test_color_fade_curve() {
         let len = 2;
@@ -668,32 +797,132 @@ mod test {
         let mut animation_tester =
             AnimationTester::new(animation, Iterations::Single, led_controller);
 
-        animation_tester.assert_state(0, [(6, HSVColor::new(0, 0, 0))]);
+        let off = HSVColor::new(0, 0, 0);
+        let half = HSVColor::new(50, 50, 50);
+        let full = HSVColor::new(100, 100, 100);
+        let sequence: [(Tick, &[(LedId, HSVColor)]); 5] = [
+            (0, &[(6, off)]),
+            (8, &[(6, half), (7, off)]),
+            (16, &[(6, full), (7, half), (8, off)]),
+            (32, &[(7, off), (8, half), (9, full)]),
+            (40, &[(9, off)]),
+        ];
+        animation_tester.assert_sequence(sequence);
+    }
 
-        animation_tester.assert_state(
-            8,
-            [(6, HSVColor::new(50, 50, 50)), (7, HSVColor::new(0, 0, 0))],
+    #[test]
+    fn test_running_light_transition_curve() {
+        let frame_at = |transition_curve: Option<Curve>, tick| {
+            let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 20>::new()));
+            let pattern = HillPattern::new(
+                1,
+                TransparentColor::opaque(HSVColor::new(100, 100, 100)),
+                Curve::Linear,
+            );
+            let animation = RunningLight::new(
+                40,
+                6u16..10,
+                pattern,
+                AnimationLen::Static(5),
+                0,
+                false,
+                BlendMode::AllChannels,
+            );
+            let animation = match transition_curve {
+                Some(curve) => animation.with_transition_curve(curve),
+                None => animation,
+            };
+            animation
+                .animate(
+                    tick,
+                    led_controller,
+                    &AnimationMeta::new(IterationState::single()),
+                )
+                .map(|c| (c.led, c.color))
+                .collect::<alloc::vec::Vec<_>>()
+        };
+
+        assert_eq!(frame_at(None, 8)[0], (6, HSVColor::new(50, 50, 50)));
+
+        let (led, eased) = frame_at(Some(Curve::EaseIn), 8)[0];
+        assert_eq!(led, 6);
+        assert!(eased.v < 50, "{:?}", eased);
+        let (_, eased) = frame_at(Some(Curve::EaseOut), 8)[0];
+        assert!(eased.v > 50, "{:?}", eased);
+
+        assert_eq!(
+            frame_at(Some(Curve::EaseIn), 16)[0],
+            (6, HSVColor::new(100, 100, 100))
         );
+    }
+
+    #[test]
+    fn test_animate_running_light_paint() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 20>::new()));
+        let red = HSVColor::new(0, 100, 100);
+        let pattern = HillPattern::new(1, TransparentColor::opaque(red), Curve::Linear);
+
+        let animation = RunningLight::new(
+            40,
+            6u16..10,
+            pattern,
+            AnimationLen::Static(5),
+            0,
+            false,
+            BlendMode::AllChannels,
+        )
+        .with_paint(true);
+        let mut animation_tester =
+            AnimationTester::new(animation, Iterations::Single, led_controller);
 
+        animation_tester.assert_state(0, [(6, HSVColor::new(0, 0, 0))]);
         animation_tester.assert_state(
             16,
             [
-                (6, HSVColor::new(100, 100, 100)),
-                (7, HSVColor::new(50, 50, 50)),
+                (6, red),
+                (7, HSVColor::new(0, 50, 50)),
                 (8, HSVColor::new(0, 0, 0)),
             ],
         );
-
         animation_tester.assert_state(
-            32,
+            22,
             [
-                (7, HSVColor::new(0, 0, 0)),
-                (8, HSVColor::new(50, 50, 50)),
-                (9, HSVColor::new(100, 100, 100)),
+                (6, red),
+                (7, red),
+                (8, HSVColor::new(0, 50, 50)),
+                (9, HSVColor::new(0, 0, 0)),
             ],
         );
+        // the tail of the light doesn't restore the leds it passed
+        animation_tester.assert_state(
+            28,
+            [(6, red), (7, red), (8, red), (9, HSVColor::new(0, 50, 50))],
+        );
+        animation_tester.assert_state(34, [(7, red), (8, red), (9, red)]);
+        animation_tester.assert_state(40, [(9, red)]);
+    }
 
-        animation_tester.assert_state(40, [(9, HSVColor::new(0, 0, 0))]);
+    #[test]
+    fn test_animate_running_light_empty_range() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 20>::new()));
+        let pattern = HillPattern::new(
+            1,
+            TransparentColor::opaque(HSVColor::new(100, 100, 100)),
+            Curve::Linear,
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+
+        for len in [AnimationLen::FullStretch, AnimationLen::Static(5)] {
+            for circle in [false, true] {
+                let animation =
+                    RunningLight::new(40, 0u16..0, pattern, len, 0, circle, BlendMode::AllChannels);
+                assert_eq!(Animation::<LedStrip<SPI, 20>>::duration(&animation), 40);
+                for tick in [0, 20, 40] {
+                    let frame = animation.animate(tick, led_controller.clone(), &meta);
+                    assert_eq!(frame.count(), 0);
+                }
+            }
+        }
     }
 
     // #[test]
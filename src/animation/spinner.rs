@@ -0,0 +1,121 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    indexing::{Indexing, LedId},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Bright arc with a fading tail, which rotates once around a circular range per period.
+///
+/// The head of the arc moves towards higher indices and wraps around at the end of the range.
+/// Leds behind the tail are turned off.
+#[derive(Debug)]
+pub struct Spinner<I> {
+    period: Ticks,
+    range: I,
+    color: HSVColor,
+    arc_len: u16,
+    tail_len: u16,
+}
+
+impl<I> Spinner<I> {
+    /// period != 0, arc_len != 0
+    pub fn new(period: Ticks, range: I, color: HSVColor, arc_len: u16, tail_len: u16) -> Self {
+        assert!(period > 0, "period must be at least 1");
+        assert!(arc_len > 0, "arc length must be at least 1");
+        Self {
+            period,
+            range,
+            color,
+            arc_len,
+            tail_len,
+        }
+    }
+
+    /// Brightness (0.0 - 1.0) of a led `distance` leds behind the head.
+    fn value_at(&self, distance: u32) -> f32 {
+        let arc_len = u32::from(self.arc_len);
+        let tail_len = u32::from(self.tail_len);
+        if distance < arc_len {
+            1.0
+        } else if distance < arc_len + tail_len {
+            1.0 - (distance - arc_len + 1) as f32 / (tail_len + 1) as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<I, S> Animation<S> for Spinner<I>
+where
+    I: Indexing,
+    S: Strip,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        _: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let len = self.range.len() as u64;
+        if len == 0 {
+            return Box::new(core::iter::empty());
+        }
+        let head = u64::from(animation_tick) * len / u64::from(self.period) % len;
+
+        let mut colorings = Vec::new();
+        for index in 0..self.range.len() {
+            let distance = (head + len - index as u64) % len;
+            let value = self.value_at(distance as u32);
+            let color = HSVColor {
+                v: (f32::from(self.color.v) * value).round() as u8,
+                ..self.color
+            };
+            let leds = self
+                .range
+                .index(LedId::try_from(index).unwrap())
+                .expect("Led index out of range");
+            colorings.extend(leds.map(|led| LedColoring::new(led, color)));
+        }
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.period
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::testing::{AnimationTester, Iterations},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    fn ring(v: [u8; 6]) -> [(LedId, HSVColor); 6] {
+        [0, 1, 2, 3, 4, 5].map(|led| (led, HSVColor::new(0, 100, v[led as usize])))
+    }
+
+    #[test]
+    fn test_spinner_rotates_and_wraps() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let spinner = Spinner::new(6, 0..6, HSVColor::new(0, 100, 100), 2, 1);
+        let mut tester = AnimationTester::new(spinner, Iterations::Single, strip);
+
+        tester.assert_state(0, ring([100, 0, 0, 0, 50, 100]));
+        tester.assert_state(1, ring([100, 100, 0, 0, 0, 50]));
+        tester.assert_state(3, ring([0, 50, 100, 100, 0, 0]));
+        tester.assert_state(5, ring([0, 0, 0, 50, 100, 100]));
+        tester.assert_state(6, ring([100, 0, 0, 0, 50, 100]));
+    }
+}
@@ -5,10 +5,11 @@ use alloc::{boxed::Box, rc::Rc};
 use crate::{
     color::{blend_colors, BlendMode, HSVColor, LedColoring, Spectrum, TransparentColor},
     color_cache::ColorCache,
-    curve::{calculate_with_curve, Curve},
+    curve::{calculate_with_curve, Curve, EaseTable},
     indexing::{Index, Indexing, LedId},
     strip::Strip,
     timeline::{Tick, Ticks},
+    util::clamp_percentage,
 };
 
 use super::{Animation, AnimationMeta};
@@ -23,6 +24,8 @@ pub struct StaticAnimation<I, SP> {
     curve: Curve,
     fade_cache: FadeCache,
     blend_mode: BlendMode,
+    ease_table: Option<Rc<EaseTable>>,
+    stagger: f32,
 }
 
 impl<I, SP> StaticAnimation<I, SP>
@@ -38,11 +41,34 @@ where
             curve,
             fade_cache: Rc::new(RefCell::new(ColorCache::new())),
             blend_mode,
+            ease_table: None,
+            stagger: 0.0,
         }
     }
+
+    /// Delays the start of the fade of led `i` by `i * stagger` ticks, for a cascading wipe.
+    ///
+    /// Every led still fades over `duration`, so the animation takes longer by the delay of the
+    /// last led.
+    pub fn with_stagger(mut self, stagger: f32) -> Self {
+        assert!(stagger >= 0.0, "stagger must not be negative");
+        self.stagger = stagger;
+        self
+    }
+
+    /// Precomputes the curve once for the whole duration instead of easing every led on
+    /// every tick. Useful for expensive curves on long strips.
+    pub fn with_ease_table(mut self) -> Self {
+        self.ease_table = Some(Rc::new(EaseTable::new(&self.curve, self.duration)));
+        self
+    }
 }
 
 impl<I, SP> StaticAnimation<I, SP> {
+    fn start_delay(stagger: f32, index: usize) -> Ticks {
+        (index as f32 * stagger).round() as Ticks
+    }
+
     fn cache_current_colors<S>(&self, led_controller: Rc<RefCell<S>>)
     where
         I: Indexing,
@@ -87,13 +113,22 @@ where
                 self.fade_cache.clone(),
                 animation_tick,
                 self.blend_mode,
+                self.ease_table.clone(),
+                self.stagger,
             )
             .flatten(),
         )
     }
 
     fn duration(&self) -> Ticks {
-        self.duration
+        let last_index = self.range.len().saturating_sub(1);
+        self.duration + Self::start_delay(self.stagger, last_index)
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.fade_cache
+            .borrow_mut()
+            .restore_to(&mut *strip.borrow_mut());
     }
 }
 
@@ -106,9 +141,15 @@ pub struct SingleBatchIterator<I, SP> {
     curve: Curve,
     current_tick: Tick,
     blend_mode: BlendMode,
+    ease_table: Option<Rc<EaseTable>>,
+    constant_color: Option<TransparentColor<HSVColor>>,
+    stagger: f32,
 }
 
-impl<I, SP> SingleBatchIterator<I, SP> {
+impl<I, SP> SingleBatchIterator<I, SP>
+where
+    SP: Spectrum<Color = HSVColor>,
+{
     fn from_batch(
         range: I,
         to_color: SP,
@@ -117,8 +158,12 @@ impl<I, SP> SingleBatchIterator<I, SP> {
         fade_cache: FadeCache,
         current_tick: Tick,
         blend_mode: BlendMode,
+        ease_table: Option<Rc<EaseTable>>,
+        stagger: f32,
     ) -> Self {
+        let constant_color = to_color.is_constant().then(|| to_color.color_at(0.0));
         Self {
+            constant_color,
             range,
             duration,
             to_color,
@@ -127,6 +172,8 @@ impl<I, SP> SingleBatchIterator<I, SP> {
             curve,
             current_tick,
             blend_mode,
+            ease_table,
+            stagger,
         }
     }
 }
@@ -144,17 +191,29 @@ where
         }
 
         let led_idx = self.range.index(self.index).unwrap();
-        let percentage = self.index as f32 / self.range.len() as f32;
-        let to_color = self.to_color.color_at(percentage);
+        let to_color = match self.constant_color {
+            Some(color) => color,
+            None => {
+                let percentage = clamp_percentage(self.index as f32 / self.range.len() as f32);
+                self.to_color.color_at(percentage)
+            }
+        };
+
+        let start_delay = StaticAnimation::<I, SP>::start_delay(self.stagger, self.index.into());
+        let current_tick = self
+            .current_tick
+            .saturating_sub(start_delay)
+            .min(self.duration);
 
         let ret = MapIterator {
             output_index: led_idx,
             curve: self.curve.clone(),
             duration: self.duration,
-            current_tick: self.current_tick,
+            current_tick,
             fade_cache: self.fade_cache.clone(),
             to_color,
             blend_mode: self.blend_mode,
+            ease_table: self.ease_table.clone(),
         };
 
         self.index += 1;
@@ -170,6 +229,7 @@ pub struct MapIterator<O> {
     fade_cache: FadeCache,
     to_color: TransparentColor<HSVColor>,
     blend_mode: BlendMode,
+    ease_table: Option<Rc<EaseTable>>,
 }
 
 impl<O> Iterator for MapIterator<O>
@@ -185,13 +245,16 @@ where
             let from_color = self.fade_cache.borrow().load_color(led).unwrap();
             let to_color = blend_colors(from_color, self.to_color, self.blend_mode);
 
-            let next_color = calculate_with_curve(
-                &self.curve,
-                self.duration,
-                &from_color,
-                &to_color,
-                self.current_tick,
-            );
+            let next_color = match self.ease_table.as_ref() {
+                Some(table) => table.calculate(&from_color, &to_color, self.current_tick),
+                None => calculate_with_curve(
+                    &self.curve,
+                    self.duration,
+                    &from_color,
+                    &to_color,
+                    self.current_tick,
+                ),
+            };
 
             LedColoring::new(led, next_color)
         })
@@ -201,15 +264,17 @@ where
 #[cfg(test)]
 mod test {
 
-    use core::cell::RefCell;
+    use core::cell::{Cell, RefCell};
 
     use alloc::rc::Rc;
 
     use crate::{
         animation::testing::{AnimationTester, Iterations},
-        color::{BlendMode, HSVColor, TransparentColor},
+        animation::{Animation, AnimationMeta, IterationState},
+        color::{BlendMode, HSVColor, Spectrum, TransparentColor},
         curve::Curve,
         mock::SPI,
+        pattern::{HillPattern, PatternAsSpectrum},
         strip::{mock::LedStrip, Strip},
     };
 
@@ -226,6 +291,129 @@ mod test {
         animation_tester.assert_state(1, (0..6).map(|led| (led, HSVColor::new(100, 0, 100))));
     }
 
+    #[test]
+    fn test_pattern_as_spectrum() {
+        let red = HSVColor::new(0, 100, 100);
+        let spectrum = PatternAsSpectrum::new(HillPattern::new(1, red, Curve::Linear), 5);
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 5>::new()));
+        let animation =
+            StaticAnimation::new(1, 0..5, spectrum, Curve::Step, BlendMode::AllChannels);
+
+        let mut animation_tester =
+            AnimationTester::new(animation, Iterations::Single, led_controller);
+        animation_tester.assert_state(
+            1,
+            [
+                (0, HSVColor::new(0, 0, 0)),
+                (1, HSVColor::new(0, 50, 50)),
+                (2, red),
+                (3, HSVColor::new(0, 50, 50)),
+                (4, HSVColor::new(0, 0, 0)),
+            ],
+        );
+    }
+
+    #[derive(Clone)]
+    struct CountingSpectrum {
+        calls: Rc<Cell<usize>>,
+        constant: bool,
+    }
+
+    impl Spectrum for CountingSpectrum {
+        type Color = HSVColor;
+
+        fn color_at(&self, _: f32) -> TransparentColor<Self::Color> {
+            self.calls.set(self.calls.get() + 1);
+            HSVColor::new(100, 0, 100).into()
+        }
+
+        fn is_transparent(&self) -> bool {
+            false
+        }
+
+        fn is_constant(&self) -> bool {
+            self.constant
+        }
+    }
+
+    #[test]
+    fn test_constant_spectrum_evaluated_once() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let meta = AnimationMeta::new(IterationState::single());
+
+        for (constant, expected_calls) in [(true, 1), (false, 6)] {
+            let calls = Rc::new(Cell::new(0));
+            let spectrum = CountingSpectrum {
+                calls: calls.clone(),
+                constant,
+            };
+            let animation =
+                StaticAnimation::new(1, 0..6, spectrum, Curve::Step, BlendMode::AllChannels);
+
+            let frame = animation.animate(1, led_controller.clone(), &meta);
+            assert!(frame
+                .map(|c| c.color)
+                .all(|c| c == HSVColor::new(100, 0, 100)));
+            assert_eq!(calls.get(), expected_calls);
+        }
+    }
+
+    #[test]
+    fn test_stagger_delays_later_leds() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let color = HSVColor::new(100, 0, 100);
+        let animation = StaticAnimation::new(4, 0..6, color, Curve::Linear, BlendMode::AllChannels)
+            .with_stagger(2.0);
+
+        assert_eq!(Animation::<LedStrip<SPI, 6>>::duration(&animation), 14);
+
+        let mut animation_tester =
+            AnimationTester::new(animation, Iterations::Single, led_controller);
+        animation_tester.assert_state(
+            4,
+            [
+                (0, color),
+                (1, HSVColor::new(100, 0, 50)),
+                (2, HSVColor::new(100, 0, 0)),
+                (3, HSVColor::new(100, 0, 0)),
+                (4, HSVColor::new(100, 0, 0)),
+                (5, HSVColor::new(100, 0, 0)),
+            ],
+        );
+        animation_tester.assert_state(14, (0..6).map(|led| (led, color)));
+        animation_tester.assert_state(20, (0..6).map(|led| (led, color)));
+    }
+
+    #[test]
+    fn test_empty_range() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let meta = AnimationMeta::new(IterationState::single());
+        let empty: &[u16] = &[];
+
+        let animation = StaticAnimation::new(
+            10,
+            0u16..0,
+            HSVColor::new(100, 0, 100),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        );
+        assert_eq!(Animation::<LedStrip<SPI, 6>>::duration(&animation), 10);
+        for tick in [0, 5, 10] {
+            let frame = animation.animate(tick, led_controller.clone(), &meta);
+            assert_eq!(frame.count(), 0);
+        }
+
+        let animation = StaticAnimation::new(
+            10,
+            empty,
+            HSVColor::new(100, 0, 100),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        );
+        let frame = animation.animate(5, led_controller, &meta);
+        assert_eq!(frame.count(), 0);
+    }
+
     #[test]
     fn test_color_cache() {
         let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
@@ -259,4 +447,35 @@ mod test {
                 .flatten(),
         );
     }
+
+    #[test]
+    fn test_ease_table_matches_direct_many_leds() {
+        let led_controller = Rc::new(RefCell::new(LedStrip::<SPI, 300>::new()));
+        let duration = 50;
+        let direct = StaticAnimation::new(
+            duration,
+            0..300,
+            HSVColor::new(200, 100, 100),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        );
+        let tabled = StaticAnimation::new(
+            duration,
+            0..300,
+            HSVColor::new(200, 100, 100),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        )
+        .with_ease_table();
+        let meta = AnimationMeta::new(IterationState::single());
+
+        for tick in 0..=duration {
+            let direct_frame = direct.animate(tick, led_controller.clone(), &meta);
+            let tabled_frame = tabled.animate(tick, led_controller.clone(), &meta);
+            for (d, t) in direct_frame.zip(tabled_frame) {
+                assert_eq!(d.led, t.led);
+                assert_eq!(d.color, t.color, "tick: {}, led: {}", tick, d.led);
+            }
+        }
+    }
 }
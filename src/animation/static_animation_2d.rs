@@ -0,0 +1,186 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use crate::{
+    color::{blend_colors, BlendMode, HSVColor, LedColoring, Spectrum},
+    color_cache::ColorCache,
+    curve::{calculate_with_curve, Curve},
+    indexing::Matrix,
+    strip::Strip,
+    timeline::{Tick, Ticks},
+    util::clamp_percentage,
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Fades a matrix to a spectrum, which is sampled along a direction.
+///
+/// The direction is given by `angle` in degrees, 0 runs from the left to the right column, 90
+/// from the top to the bottom row. Like [StaticAnimation](super::StaticAnimation) the leds fade
+/// from their current color to the spectrum.
+#[derive(Debug)]
+pub struct StaticAnimation2D<SP> {
+    duration: Ticks,
+    matrix: Matrix,
+    to: SP,
+    angle: f32,
+    curve: Curve,
+    fade_cache: Rc<RefCell<ColorCache>>,
+    blend_mode: BlendMode,
+}
+
+impl<SP> StaticAnimation2D<SP>
+where
+    SP: Spectrum,
+{
+    /// duration != 0, min. 1
+    pub fn new(
+        duration: Ticks,
+        matrix: Matrix,
+        to: SP,
+        angle: f32,
+        curve: Curve,
+        blend_mode: BlendMode,
+    ) -> Self {
+        Self {
+            duration,
+            matrix,
+            to,
+            angle,
+            curve,
+            fade_cache: Rc::new(RefCell::new(ColorCache::new())),
+            blend_mode,
+        }
+    }
+}
+
+impl<SP> StaticAnimation2D<SP> {
+    /// Returns the percentage of every led, projected on the direction and normalized to 0.0 - 1.0.
+    fn percentages(&self) -> Vec<f32> {
+        let (dy, dx) = self.angle.to_radians().sin_cos();
+        let project = |x: u16, y: u16| x as f32 * dx + y as f32 * dy;
+
+        let last_x = self.matrix.width().saturating_sub(1);
+        let last_y = self.matrix.height().saturating_sub(1);
+        let corners = [
+            project(0, 0),
+            project(last_x, 0),
+            project(0, last_y),
+            project(last_x, last_y),
+        ];
+        let min = corners.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = corners.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let span = max - min;
+
+        (0..self.matrix.height())
+            .flat_map(|y| (0..self.matrix.width()).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if span <= f32::EPSILON {
+                    0.0
+                } else {
+                    clamp_percentage((project(x, y) - min) / span)
+                }
+            })
+            .collect()
+    }
+}
+
+impl<S, SP> Animation<S> for StaticAnimation2D<SP>
+where
+    S: Strip,
+    SP: Spectrum<Color = HSVColor>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        _: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let mut fade_cache = self.fade_cache.borrow_mut();
+        if fade_cache.cache_size() == 0 {
+            let strip = strip.borrow();
+            for y in 0..self.matrix.height() {
+                for x in 0..self.matrix.width() {
+                    let led = self.matrix.led_at(x, y);
+                    let _ = fade_cache.cache_color(led, &strip.get_color_of_led(led).into());
+                }
+            }
+        }
+
+        let colorings = self
+            .percentages()
+            .into_iter()
+            .zip(0..)
+            .map(|(percentage, led)| {
+                let from_color = fade_cache.load_color(led).unwrap();
+                let to_color =
+                    blend_colors(from_color, self.to.color_at(percentage), self.blend_mode);
+                let color = calculate_with_curve(
+                    &self.curve,
+                    self.duration,
+                    &from_color,
+                    &to_color,
+                    animation_tick,
+                );
+                LedColoring::new(led, color)
+            })
+            .collect::<Vec<_>>();
+
+        Box::new(colorings.into_iter())
+    }
+
+    fn duration(&self) -> Ticks {
+        self.duration
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.fade_cache
+            .borrow_mut()
+            .restore_to(&mut *strip.borrow_mut());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::IterationState, color::RainbowSpectrum, mock::SPI, strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_horizontal_gradient() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 16>::new()));
+        let matrix = Matrix::new(4, 4);
+        let spectrum =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(300, 100, 100));
+        let animation = StaticAnimation2D::new(
+            1,
+            matrix,
+            spectrum,
+            0.0,
+            Curve::Step,
+            BlendMode::AllChannels,
+        );
+
+        let frame = animation
+            .animate(1, strip, &AnimationMeta::new(IterationState::single()))
+            .map(|c| c.color)
+            .collect::<Vec<_>>();
+        let color_at = |x, y| frame[usize::from(matrix.led_at(x, y))];
+
+        for x in 0..4 {
+            for y in 1..4 {
+                assert_eq!(color_at(x, y), color_at(x, 0));
+            }
+        }
+        for x in 1..4 {
+            assert_ne!(color_at(x, 0), color_at(x - 1, 0));
+        }
+        assert_eq!(color_at(0, 0), HSVColor::new(0, 100, 100));
+        assert_eq!(color_at(3, 0), HSVColor::new(300, 100, 100));
+    }
+}
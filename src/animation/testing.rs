@@ -79,17 +79,20 @@ where
         for (led_id, color) in should_state {
             let next_led = led_iter.next();
             match next_led {
-                None => panic!("LED iterate has not the same length as should_state"),
+                None => panic!(
+                    "tick {}: LED iterate has not the same length as should_state",
+                    tick
+                ),
                 Some(led_colored) => {
                     assert_eq!(
                         led_colored.led, led_id,
-                        "LED has wrong id: {:?}, should be: {:?}",
-                        led_colored.led, led_id
+                        "tick {}: LED has wrong id: {:?}, should be: {:?}",
+                        tick, led_colored.led, led_id
                     );
                     assert_eq!(
                         led_colored.color, color,
-                        "LED with id {:?} should have color: {:?} but has: {:?}",
-                        led_id, color, led_colored.color,
+                        "tick {}: LED with id {:?} should have color: {:?} but has: {:?}",
+                        tick, led_id, color, led_colored.color,
                     );
                 }
             }
@@ -97,7 +100,18 @@ where
         assert_matches!(
             led_iter.next(),
             None,
-            "LED iterate has not the same length as should_state"
+            "tick {}: LED iterate has not the same length as should_state",
+            tick
         )
     }
+
+    /// [assert_state](Self::assert_state) for every `(tick, should_state)` in order.
+    pub fn assert_sequence<'a, T>(&mut self, sequence: T)
+    where
+        T: IntoIterator<Item = (Tick, &'a [(LedId, HSVColor)])>,
+    {
+        for (tick, should_state) in sequence {
+            self.assert_state(tick, should_state.iter().copied());
+        }
+    }
 }
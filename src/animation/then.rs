@@ -0,0 +1,121 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Runs `second` after `first` has finished, a lightweight alternative to a timeline for simple
+/// sequences.
+///
+/// Like every animation `first` renders the ticks `0..=duration`, so its last tick is shown
+/// before `second` starts with its tick 0 on the following tick.
+#[derive(Debug)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Then<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A, B, S> Animation<S> for Then<A, B>
+where
+    S: Strip,
+    A: Animation<S>,
+    B: Animation<S>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let first_duration = self.first.duration();
+        if animation_tick <= first_duration {
+            self.first.animate(animation_tick, strip, animation_meta)
+        } else {
+            self.second
+                .animate(animation_tick - (first_duration + 1), strip, animation_meta)
+        }
+    }
+
+    fn duration(&self) -> Ticks {
+        // the last tick of first takes a tick on its own
+        self.first.duration() + 1 + self.second.duration()
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        self.first.name()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.second.reset(strip.clone());
+        self.first.reset(strip);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{
+        animation::{IterationState, StaticAnimation},
+        color::BlendMode,
+        curve::Curve,
+        indexing::LedId,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_then_hands_over_at_boundary() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let animation = Then::new(
+            StaticAnimation::new(
+                2,
+                0..2,
+                HSVColor::red(),
+                Curve::Linear,
+                BlendMode::AllChannels,
+            ),
+            StaticAnimation::new(
+                4,
+                2..4,
+                HSVColor::blue(),
+                Curve::Linear,
+                BlendMode::AllChannels,
+            ),
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+        let frame = |tick| {
+            animation
+                .animate(tick, strip.clone(), &meta)
+                .map(|c| (c.led, c.color))
+                .collect::<Vec<(LedId, HSVColor)>>()
+        };
+
+        assert_eq!(Animation::<LedStrip<SPI, 4>>::duration(&animation), 7);
+        assert!(frame(0).iter().all(|c| c.0 < 2));
+        assert!(frame(1).iter().all(|c| c.0 < 2));
+        assert_eq!(frame(2), [(0, HSVColor::red()), (1, HSVColor::red())]);
+        assert_eq!(
+            frame(3),
+            [
+                (2, HSVColor::new(240, 100, 0)),
+                (3, HSVColor::new(240, 100, 0))
+            ]
+        );
+        assert_eq!(frame(7), [(2, HSVColor::blue()), (3, HSVColor::blue())]);
+    }
+}
@@ -0,0 +1,131 @@
+use core::cell::RefCell;
+
+use alloc::{boxed::Box, rc::Rc};
+
+use crate::{
+    color::{HSVColor, LedColoring},
+    strip::Strip,
+    timeline::{Tick, Ticks},
+};
+
+use super::{Animation, AnimationMeta};
+
+/// Renders the inner animation only every `n`th tick and holds the frame in between.
+///
+/// The tick is snapped down to the nearest multiple of `n`, the duration is unchanged. The last
+/// tick is never snapped, so the end state of the inner animation is always rendered. Useful for
+/// very slow effects, which don't need a new frame every tick.
+#[derive(Debug)]
+pub struct TickDivider<A> {
+    animation: A,
+    n: Ticks,
+}
+
+impl<A> TickDivider<A> {
+    /// n != 0
+    pub fn new(animation: A, n: Ticks) -> Self {
+        assert!(n > 0, "tick divider must be at least 1");
+        Self { animation, n }
+    }
+}
+
+impl<A, S> Animation<S> for TickDivider<A>
+where
+    S: Strip,
+    A: Animation<S>,
+{
+    fn animate(
+        &self,
+        animation_tick: Tick,
+        strip: Rc<RefCell<S>>,
+        animation_meta: &AnimationMeta,
+    ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+        let snapped_tick = if animation_tick >= self.animation.duration() {
+            animation_tick
+        } else {
+            animation_tick - animation_tick % self.n
+        };
+        self.animation.animate(snapped_tick, strip, animation_meta)
+    }
+
+    fn duration(&self) -> Ticks {
+        self.animation.duration()
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        self.animation.name()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        self.animation.reset(strip)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::{
+        animation::{IterationState, StaticAnimation},
+        color::BlendMode,
+        curve::Curve,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_tick_divider_holds_frame() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let animation = TickDivider::new(
+            StaticAnimation::new(
+                12,
+                0..4,
+                HSVColor::new(0, 100, 100),
+                Curve::Linear,
+                BlendMode::AllChannels,
+            ),
+            4,
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+        let frame = |tick| {
+            animation
+                .animate(tick, strip.clone(), &meta)
+                .map(|c| c.color)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(Animation::<LedStrip<SPI, 4>>::duration(&animation), 12);
+        assert_eq!(frame(4), frame(5));
+        assert_eq!(frame(4), frame(7));
+        assert_ne!(frame(4), frame(8));
+        assert_eq!(frame(8)[0], HSVColor::new(0, 100, 66));
+    }
+
+    #[test]
+    fn test_tick_divider_renders_last_tick() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let animation = TickDivider::new(
+            StaticAnimation::new(
+                10,
+                0..4,
+                HSVColor::red(),
+                Curve::Linear,
+                BlendMode::AllChannels,
+            ),
+            4,
+        );
+        let meta = AnimationMeta::new(IterationState::single());
+        let frame = |tick| {
+            animation
+                .animate(tick, strip.clone(), &meta)
+                .map(|c| c.color)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(frame(8), frame(9));
+        assert_ne!(frame(8), frame(10));
+        assert!(frame(10).iter().all(|c| *c == HSVColor::red()));
+    }
+}
@@ -1,9 +1,11 @@
 use core::{
-    cmp::max,
+    cell::Cell,
+    cmp::min,
     fmt::Debug,
     ops::{Add, Index, IndexMut, Mul, Sub},
 };
 
+use alloc::{rc::Rc, string::String, vec::Vec};
 use keyframe::CanTween;
 // indicates a warning because abs() exists for f32 with std, but no_std doesn't have it
 #[allow(unused_imports)]
@@ -13,7 +15,8 @@ use rgb::RGB8;
 use crate::{
     curve::{calculate_with_curve_percentage, Curve},
     indexing::LedId,
-    util::{max_3, min_3, wrap_on},
+    keyframed::Keyframed,
+    util::{clamp_percentage, max_3, min_3, range_map_to_0_1, wrap_on},
 };
 
 const MAX_RGB_VALUE: u8 = 255;
@@ -28,6 +31,22 @@ pub trait SpectrumExt {
     fn darken(self, amount: u8) -> DarkenedSpectrum<Self>
     where
         Self: Sized;
+
+    fn gamma(self, gamma: f32) -> GammaSpectrum<Self>
+    where
+        Self: Sized;
+
+    fn reversed(self) -> ReversedSpectrum<Self>
+    where
+        Self: Sized;
+
+    fn chain<B>(self, other: B, at: f32) -> ConcatSpectrum<Self, B>
+    where
+        Self: Sized;
+
+    fn tiled(self, times: f32) -> TiledSpectrum<Self>
+    where
+        Self: Sized;
 }
 
 impl<S> SpectrumExt for S
@@ -48,25 +67,214 @@ where
     where
         Self: Sized,
     {
-        DarkenedSpectrum(self, amount)
+        DarkenedSpectrum::new(self, amount)
+    }
+
+    fn gamma(self, gamma: f32) -> GammaSpectrum<Self>
+    where
+        Self: Sized,
+    {
+        GammaSpectrum(self, gamma)
+    }
+
+    fn reversed(self) -> ReversedSpectrum<Self>
+    where
+        Self: Sized,
+    {
+        ReversedSpectrum(self)
+    }
+
+    fn chain<B>(self, other: B, at: f32) -> ConcatSpectrum<Self, B>
+    where
+        Self: Sized,
+    {
+        ConcatSpectrum::new(self, other, at)
+    }
+
+    fn tiled(self, times: f32) -> TiledSpectrum<Self>
+    where
+        Self: Sized,
+    {
+        TiledSpectrum::new(self, times)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct DarkenedSpectrum<S>(S, u8);
+#[derive(Debug, Clone)]
+enum DarkenAmount {
+    Fixed(u8),
+    Shared(Rc<Cell<u8>>),
+}
+
+impl DarkenAmount {
+    fn get(&self) -> u8 {
+        match self {
+            DarkenAmount::Fixed(amount) => *amount,
+            DarkenAmount::Shared(amount) => amount.get(),
+        }
+    }
+}
+
+/// Darkens every color of the spectrum by an amount.
+///
+/// With a shared amount the spectrum can be darkened while animating, e.g. to let a gradient
+/// pulse.
+#[derive(Debug, Clone)]
+pub struct DarkenedSpectrum<S>(S, DarkenAmount);
+
+impl<S> DarkenedSpectrum<S> {
+    pub fn new(spectrum: S, amount: u8) -> Self {
+        Self(spectrum, DarkenAmount::Fixed(amount))
+    }
+
+    pub fn shared(spectrum: S, amount: Rc<Cell<u8>>) -> Self {
+        Self(spectrum, DarkenAmount::Shared(amount))
+    }
+}
 
 impl<S: Spectrum<Color = C>, C: ColorExt> Spectrum for DarkenedSpectrum<S> {
     type Color = C;
 
     fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
         let mut c = self.0.color_at(percentage);
-        c.color = c.color.darken(self.1);
+        c.color = c.color.darken(self.1.get());
+        c
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.0.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.0.is_constant()
+    }
+}
+
+/// Applies gamma correction to the value channel of every color of the spectrum.
+#[derive(Debug, Clone, Copy)]
+pub struct GammaSpectrum<S>(S, f32);
+
+impl<S: Spectrum<Color = HSVColor>> Spectrum for GammaSpectrum<S> {
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let mut c = self.0.color_at(percentage);
+        c.color.v = ((c.color.v as f32 / 100.0).powf(self.1) * 100.0).round() as u8;
         c
     }
 
     fn is_transparent(&self) -> bool {
         self.0.is_transparent()
     }
+
+    fn is_constant(&self) -> bool {
+        self.0.is_constant()
+    }
+}
+
+/// Mirrors a spectrum, `color_at(p)` samples `1.0 - p`.
+#[derive(Debug, Clone, Copy)]
+pub struct ReversedSpectrum<S>(S);
+
+impl<S: Spectrum> Spectrum for ReversedSpectrum<S> {
+    type Color = S::Color;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        self.0.color_at(1.0 - clamp_percentage(percentage))
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.0.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.0.is_constant()
+    }
+}
+
+/// Stitches two spectra together, `a` covers `0.0..at` and `b` covers `at..=1.0`.
+#[derive(Debug, Clone, Copy)]
+pub struct ConcatSpectrum<A, B> {
+    a: A,
+    b: B,
+    at: f32,
+}
+
+impl<A, B> ConcatSpectrum<A, B> {
+    pub fn new(a: A, b: B, at: f32) -> Self {
+        Self {
+            a,
+            b,
+            at: clamp_percentage(at),
+        }
+    }
+}
+
+impl<A, B> Spectrum for ConcatSpectrum<A, B>
+where
+    A: Spectrum<Color = HSVColor>,
+    B: Spectrum<Color = HSVColor>,
+{
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let percentage = clamp_percentage(percentage);
+        if percentage < self.at || self.at >= 1.0 {
+            self.a.color_at(percentage / self.at)
+        } else {
+            self.b.color_at((percentage - self.at) / (1.0 - self.at))
+        }
+    }
+
+    fn is_transparent(&self) -> bool {
+        (self.at > 0.0 && self.a.is_transparent()) || (self.at < 1.0 && self.b.is_transparent())
+    }
+
+    fn is_constant(&self) -> bool {
+        if self.at <= 0.0 {
+            self.b.is_constant()
+        } else if self.at >= 1.0 {
+            self.a.is_constant()
+        } else {
+            false
+        }
+    }
+}
+
+/// Repeats a spectrum `times` times over `0.0..=1.0`. With a whole number of tiles `1.0` samples
+/// the last color of the spectrum instead of wrapping around to the first, otherwise the last
+/// tile is cut at `times.fract()`.
+#[derive(Debug, Clone, Copy)]
+pub struct TiledSpectrum<S> {
+    inner: S,
+    times: f32,
+}
+
+impl<S> TiledSpectrum<S> {
+    /// times > 0.0
+    pub fn new(inner: S, times: f32) -> Self {
+        assert!(times > 0.0, "times must be greater than 0");
+        Self { inner, times }
+    }
+}
+
+impl<S: Spectrum> Spectrum for TiledSpectrum<S> {
+    type Color = S::Color;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let percentage = clamp_percentage(percentage);
+        if percentage >= 1.0 && self.times.fract() == 0.0 {
+            return self.inner.last_color();
+        }
+        self.inner.color_at((percentage * self.times).fract())
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.inner.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.inner.is_constant()
+    }
 }
 
 pub trait Spectrum {
@@ -77,6 +285,12 @@ pub trait Spectrum {
 
     fn is_transparent(&self) -> bool;
 
+    /// Returns `true` if the spectrum has the same color at every percentage, so it only needs
+    /// to be evaluated once.
+    fn is_constant(&self) -> bool {
+        false
+    }
+
     fn first_color(&self) -> TransparentColor<Self::Color> {
         self.color_at(0.0)
     }
@@ -84,9 +298,28 @@ pub trait Spectrum {
     fn last_color(&self) -> TransparentColor<Self::Color> {
         self.color_at(1.0)
     }
+
+    /// Same as [color_at](Self::color_at) but converted to RGB, returns the color and its
+    /// transparency.
+    fn color_at_rgb(&self, percentage: f32) -> (Color, f32)
+    where
+        Self::Color: Into<Color>,
+    {
+        let c = self.color_at(percentage);
+        (c.color.into(), c.transparency)
+    }
+
+    /// Fills `out` with colors evenly spread over the spectrum, slot `i` gets
+    /// `color_at(i / (out.len() - 1))`. A single slot gets the first color.
+    fn sample_into(&self, out: &mut [TransparentColor<Self::Color>]) {
+        let last = out.len().saturating_sub(1).max(1) as f32;
+        for (i, slot) in out.iter_mut().enumerate() {
+            *slot = self.color_at(i as f32 / last);
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct PeakSpectrum {
     pub from_color: TransparentColor<HSVColor>,
     pub peak_color: TransparentColor<HSVColor>,
@@ -152,6 +385,232 @@ impl Spectrum for RainbowSpectrum {
     }
 }
 
+#[derive(Debug, Clone)]
+enum BlendRatio {
+    Fixed(f32),
+    Shared(Rc<Cell<f32>>),
+}
+
+impl BlendRatio {
+    fn get(&self) -> f32 {
+        match self {
+            BlendRatio::Fixed(t) => *t,
+            BlendRatio::Shared(t) => t.get(),
+        }
+    }
+}
+
+/// Blends the colors of two spectra by the ratio `t` (0.0 = only `a`, 1.0 = only `b`).
+///
+/// With a shared ratio the blend can be changed while animating, e.g. to crossfade between two
+/// color themes.
+#[derive(Debug, Clone)]
+pub struct BlendedSpectrum<A, B> {
+    a: A,
+    b: B,
+    t: BlendRatio,
+}
+
+impl<A, B> BlendedSpectrum<A, B> {
+    pub fn new(a: A, b: B, t: f32) -> Self {
+        Self {
+            a,
+            b,
+            t: BlendRatio::Fixed(t),
+        }
+    }
+
+    pub fn shared(a: A, b: B, t: Rc<Cell<f32>>) -> Self {
+        Self {
+            a,
+            b,
+            t: BlendRatio::Shared(t),
+        }
+    }
+}
+
+pub fn blend_spectra<A, B>(a: A, b: B, t: f32) -> BlendedSpectrum<A, B> {
+    BlendedSpectrum::new(a, b, t)
+}
+
+impl<A, B> Spectrum for BlendedSpectrum<A, B>
+where
+    A: Spectrum<Color = HSVColor>,
+    B: Spectrum<Color = HSVColor>,
+{
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let t = self.t.get().clamp(0.0, 1.0);
+        let a = self.a.color_at(percentage);
+        let b = self.b.color_at(percentage);
+
+        let color = blend_colors(
+            a.color,
+            b.color.with_transparency(1.0 - t),
+            BlendMode::AllChannels,
+        );
+        let transparency = a.transparency * (1.0 - t) + b.transparency * t;
+        TransparentColor::new(color, transparency)
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.a.is_transparent() || self.b.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.a.is_constant() && self.b.is_constant()
+    }
+}
+
+/// Spectrum between two colors where the hue always takes the shorter direction around the
+/// color wheel, e.g. red to magenta passes pink instead of green.
+///
+/// [RainbowSpectrum] takes the shorter direction as well, since easing [HSVColor] does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShortestArcSpectrum {
+    pub from_color: TransparentColor<HSVColor>,
+    pub to_color: TransparentColor<HSVColor>,
+}
+
+impl ShortestArcSpectrum {
+    pub fn new(
+        from_color: impl Into<TransparentColor<HSVColor>>,
+        to_color: impl Into<TransparentColor<HSVColor>>,
+    ) -> Self {
+        Self {
+            from_color: from_color.into(),
+            to_color: to_color.into(),
+        }
+    }
+}
+
+impl Spectrum for ShortestArcSpectrum {
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let percentage = clamp_percentage(percentage);
+        let mut color = calculate_with_curve_percentage(
+            &Curve::Linear,
+            &self.from_color,
+            &self.to_color,
+            percentage,
+        );
+
+        let (from, to) = (self.from_color.color, self.to_color.color);
+        // fades from or to off keep the hue of the lit color
+        if from.v > 0 && to.v > 0 {
+            color.color.h = lerp_hue_shortest(from.h, to.h, percentage);
+        }
+        color
+    }
+
+    fn is_transparent(&self) -> bool {
+        !self.from_color.is_opaque() || !self.to_color.is_opaque()
+    }
+}
+
+/// Spectrum with multiple color stops `(position, color)`, interpolated linearly in between.
+///
+/// Before the first and after the last stop the color of that stop is returned. This is a
+/// [Keyframed] spectrum whose segments all use [Curve::Linear].
+#[derive(Debug, Clone)]
+pub struct GradientSpectrum(Keyframed);
+
+impl GradientSpectrum {
+    /// stops.len() != 0 and every position is in range 0.0..=1.0, the stops are sorted by their
+    /// position.
+    pub fn new(stops: Vec<(f32, TransparentColor<HSVColor>)>) -> Self {
+        assert!(!stops.is_empty(), "at least one stop is required");
+        assert!(
+            stops.iter().all(|s| (0.0..=1.0).contains(&s.0)),
+            "stop positions must be in range 0.0..=1.0"
+        );
+        Self(Keyframed::new(stops))
+    }
+}
+
+impl Spectrum for GradientSpectrum {
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        self.0.color_at(percentage)
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.0.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.0.is_constant()
+    }
+}
+
+/// Shifts the sampling of a spectrum by a phase, `color_at(p)` samples `(p + phase) mod 1.0`.
+///
+/// The phase is shared, so changing it from outside scrolls the spectrum of every animation
+/// using it.
+#[derive(Debug, Clone)]
+pub struct PhasedSpectrum<S> {
+    spectrum: S,
+    phase: Rc<Cell<f32>>,
+}
+
+impl<S> PhasedSpectrum<S> {
+    pub fn new(spectrum: S, phase: Rc<Cell<f32>>) -> Self {
+        Self { spectrum, phase }
+    }
+}
+
+impl<S: Spectrum> Spectrum for PhasedSpectrum<S> {
+    type Color = S::Color;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let shifted = percentage + self.phase.get();
+        let shifted = if (0.0..=1.0).contains(&shifted) {
+            shifted
+        } else {
+            shifted - shifted.floor()
+        };
+        self.spectrum.color_at(shifted)
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.spectrum.is_transparent()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.spectrum.is_constant()
+    }
+}
+
+/// Maps readings of a sensor within `[min, max]` to the colors of a [Spectrum].
+///
+/// Readings outside of the range are clamped to the first or last color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorMapping<SP> {
+    spectrum: SP,
+    min: f32,
+    max: f32,
+}
+
+impl<SP> SensorMapping<SP>
+where
+    SP: Spectrum<Color = HSVColor>,
+{
+    /// min < max
+    pub fn new(spectrum: SP, min: f32, max: f32) -> Self {
+        assert!(min < max, "min must be smaller than max");
+        Self { spectrum, min, max }
+    }
+
+    pub fn color_for(&self, value: f32) -> TransparentColor<HSVColor> {
+        let value = value.clamp(self.min, self.max);
+        self.spectrum
+            .color_at(range_map_to_0_1(value, self.min, self.max))
+    }
+}
+
 pub struct MappedColor<C, F> {
     color: C,
     mapping: F,
@@ -202,6 +661,7 @@ where
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransparentColor<C> {
     pub color: C,
     pub transparency: f32,
@@ -234,6 +694,23 @@ impl<C> TransparentColor<C> {
     pub fn is_opaque(&self) -> bool {
         self.transparency == 0.0
     }
+
+    /// Creates a color from an 8-bit alpha, `255` is opaque and `0` fully transparent.
+    pub fn from_alpha8(color: C, alpha: u8) -> Self {
+        Self::new(color, 1.0 - f32::from(alpha) / 255.0)
+    }
+
+    /// The transparency as 8-bit alpha, `255` is opaque and `0` fully transparent.
+    pub fn alpha8(&self) -> u8 {
+        ((1.0 - clamp_percentage(self.transparency)) * 255.0).round() as u8
+    }
+}
+
+impl TransparentColor<HSVColor> {
+    /// Flattens the color onto `background`, see [blend_colors].
+    pub fn over(&self, background: HSVColor, mode: BlendMode) -> HSVColor {
+        blend_colors(background, *self, mode)
+    }
 }
 
 impl From<HSVColor> for TransparentColor<HSVColor> {
@@ -261,14 +738,27 @@ where
     fn is_transparent(&self) -> bool {
         !self.is_opaque()
     }
+
+    fn is_constant(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendMode {
     // interpolates linearly between the two colors
     AllChannels,
     // interpolates linearly between the two colors, but only for the value channel
     ValueOnly,
+    // brightens, 1 - (1 - a) * (1 - b) per RGB channel
+    Screen,
+    // darkens, a * b per RGB channel
+    Multiply,
+    // interpolates only the hue along the shorter arc, keeps saturation and value of the base
+    HueOnly,
+    // interpolates only the saturation, keeps hue and value of the base
+    SaturationOnly,
 }
 
 impl Default for BlendMode {
@@ -285,29 +775,95 @@ pub fn blend_colors(
     let base_color = color;
     let transparency = 1.0 - transparent_color.transparency;
     let blend_color = transparent_color.color;
-
-    let h = match mode {
-        BlendMode::AllChannels => {
-            (blend_color.h as f32 * transparency + base_color.h as f32 * (1.0 - transparency))
-                as u16
-        }
-        BlendMode::ValueOnly => blend_color.h,
+    let lerp = |blend: u8, base: u8| {
+        (blend as f32 * transparency + base as f32 * (1.0 - transparency)) as u8
     };
 
-    let s = match mode {
-        BlendMode::AllChannels => {
-            (blend_color.s as f32 * transparency + base_color.s as f32 * (1.0 - transparency)) as u8
+    match mode {
+        BlendMode::AllChannels => HSVColor {
+            h: (blend_color.h as f32 * transparency + base_color.h as f32 * (1.0 - transparency))
+                as u16,
+            s: lerp(blend_color.s, base_color.s),
+            v: lerp(blend_color.v, base_color.v),
+        },
+        BlendMode::ValueOnly => HSVColor {
+            h: blend_color.h,
+            s: blend_color.s,
+            v: lerp(blend_color.v, base_color.v),
+        },
+        BlendMode::HueOnly => HSVColor {
+            h: lerp_hue_shortest(base_color.h, blend_color.h, transparency),
+            ..base_color
+        },
+        BlendMode::SaturationOnly => HSVColor {
+            s: lerp(blend_color.s, base_color.s),
+            ..base_color
+        },
+        BlendMode::Screen | BlendMode::Multiply => {
+            blend_rgb(base_color, blend_color, transparency, mode)
         }
-        BlendMode::ValueOnly => blend_color.s,
-    };
+    }
+}
 
-    let v =
-        (blend_color.v as f32 * transparency + base_color.v as f32 * (1.0 - transparency)) as u8;
+/// Interpolates the hue along the shorter arc around the color wheel, e.g. 350 -> 30 passes 0.
+fn lerp_hue_shortest<F: num_traits::Float>(from: u16, to: u16, t: F) -> u16 {
+    let mut delta = i32::from(to) - i32::from(from);
+    if delta > 180 {
+        delta -= 360;
+    } else if delta < -180 {
+        delta += 360;
+    }
+    let offset = t
+        .mul(num_traits::NumCast::from(delta).unwrap())
+        .to_i32()
+        .unwrap();
+    (i32::from(from) + offset).rem_euclid(360) as u16
+}
 
-    HSVColor { h, s, v }
+fn blend_rgb(base: HSVColor, blend: HSVColor, opacity: f32, mode: BlendMode) -> HSVColor {
+    let base = Color::from(base);
+    let blend = Color::from(blend);
+    let mut blended = base;
+    for channel in 0..3 {
+        let a = base[channel] as f32 / 255.0;
+        let b = blend[channel] as f32 / 255.0;
+        let mixed = match mode {
+            BlendMode::Screen => 1.0 - (1.0 - a) * (1.0 - b),
+            _ => a * b,
+        };
+        let value = mixed * opacity + a * (1.0 - opacity);
+        blended[channel] = (value * 255.0).round() as u8;
+    }
+    blended.into()
 }
 
-impl<C: CanTween> CanTween for TransparentColor<C> {
+/// Composites `src` over `dst` in premultiplied RGB, `out = src + dst * (1 - src_alpha)` with
+/// the alpha `1 - transparency`.
+///
+/// Both colors are premultiplied with their alpha, composited and the result is divided by its
+/// alpha again. Unlike [blend_colors] the transparency of `dst` is respected, so layers can be
+/// stacked without darkening. Composite from the bottom layer up, the result is the `dst` of the
+/// next layer.
+pub fn blend_premultiplied(
+    dst: TransparentColor<Color>,
+    src: TransparentColor<Color>,
+) -> TransparentColor<Color> {
+    let src_alpha = 1.0 - clamp_percentage(src.transparency);
+    let dst_alpha = 1.0 - clamp_percentage(dst.transparency);
+    let alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+
+    let mut color = Color::off();
+    if alpha > 0.0 {
+        for channel in 0..3 {
+            let premultiplied = src.color[channel] as f32 * src_alpha
+                + dst.color[channel] as f32 * dst_alpha * (1.0 - src_alpha);
+            color[channel] = (premultiplied / alpha).round().min(MAX_RGB_VALUE as f32) as u8;
+        }
+    }
+    TransparentColor::new(color, 1.0 - alpha)
+}
+
+impl<C: CanTween> CanTween for TransparentColor<C> {
     fn ease(from: Self, to: Self, time: impl Float) -> Self {
         let color = C::ease(from.color, to.color, time);
         let transparency = f32::ease(from.transparency, to.transparency, time);
@@ -326,9 +882,19 @@ pub trait ColorExt {
     fn darken(self, amount: u8) -> Self;
 
     fn brighten(self, amount: u8) -> Self;
+
+    /// Photographic negative of the color, as suits the color type, e.g. [HSVColor] rotates the
+    /// hue and inverts the value. Defaults to the RGB complement (see [Color::inverted]).
+    fn invert(self) -> Self
+    where
+        Self: Sized + From<Color> + Into<Color>,
+    {
+        Self::from(Into::<Color>::into(self).inverted())
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     r: u8,
     g: u8,
@@ -388,6 +954,47 @@ impl Color {
         }
     }
 
+    /// Applies `out = (in / 255)^gamma * 255` to every channel, e.g. right before writing the
+    /// color to the strip.
+    pub fn gamma_correct(self, gamma: f32) -> Self {
+        let correct = |channel: u8| {
+            ((channel as f32 / MAX_RGB_VALUE as f32).powf(gamma) * MAX_RGB_VALUE as f32).round()
+                as u8
+        };
+        Self {
+            r: correct(self.r),
+            g: correct(self.g),
+            b: correct(self.b),
+        }
+    }
+
+    /// [Color::gamma_correct] with the common gamma of 2.2.
+    pub fn gamma_2_2(self) -> Self {
+        self.gamma_correct(2.2)
+    }
+
+    /// Perceived brightness with the Rec. 709 weights `0.2126 r + 0.7152 g + 0.0722 b`.
+    pub fn luma(self) -> u8 {
+        (0.2126 * self.r as f32 + 0.7152 * self.g as f32 + 0.0722 * self.b as f32)
+            .round()
+            .min(MAX_RGB_VALUE as f32) as u8
+    }
+
+    /// Sets every channel to the [luma](Self::luma) of the color.
+    pub fn grayscale(self) -> Self {
+        let luma = self.luma();
+        Self::init(luma, luma, luma)
+    }
+
+    /// RGB complement, every channel becomes `255 - channel`.
+    pub const fn inverted(&self) -> Self {
+        Self {
+            r: MAX_RGB_VALUE - self.r,
+            g: MAX_RGB_VALUE - self.g,
+            b: MAX_RGB_VALUE - self.b,
+        }
+    }
+
     pub const fn with_transparency(self, transparency: f32) -> TransparentColor<Self> {
         TransparentColor::new(self, transparency)
     }
@@ -416,9 +1023,9 @@ impl Add for Color {
 
     fn add(self, rhs: Self) -> Self::Output {
         Color {
-            r: self.r + rhs.r,
-            g: self.g + rhs.g,
-            b: self.b + rhs.b,
+            r: self.r.saturating_add(rhs.r),
+            g: self.g.saturating_add(rhs.g),
+            b: self.b.saturating_add(rhs.b),
         }
     }
 }
@@ -428,9 +1035,9 @@ impl Sub for Color {
 
     fn sub(self, rhs: Self) -> Self::Output {
         Color {
-            r: self.r - rhs.r,
-            g: self.g - rhs.g,
-            b: self.b - rhs.b,
+            r: self.r.saturating_sub(rhs.r),
+            g: self.g.saturating_sub(rhs.g),
+            b: self.b.saturating_sub(rhs.b),
         }
     }
 }
@@ -453,7 +1060,15 @@ where
 
 impl CanTween for Color {
     fn ease(from: Self, to: Self, time: impl keyframe::num_traits::Float) -> Self {
-        from + (to - from) * time
+        // per channel in f32, the saturating Sub would clamp falling channels
+        let time = time.to_f32().expect("could not parse float");
+        let ease =
+            |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * time).round() as u8;
+        Color {
+            r: ease(from.r, to.r),
+            g: ease(from.g, to.g),
+            b: ease(from.b, to.b),
+        }
     }
 }
 
@@ -503,6 +1118,72 @@ impl IndexMut<u8> for Color {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// Length without the optional `#`, only 3 and 6 digits are valid.
+    InvalidLength(usize),
+    InvalidDigit(char),
+}
+
+impl Color {
+    /// Parses `"#RRGGBB"`, `"RRGGBB"`, `"#RGB"` or `"RGB"`, the digits are case insensitive.
+    pub fn from_hex(s: &str) -> Result<Self, ParseColorError> {
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        let nibble = |c: char| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(ParseColorError::InvalidDigit(c))
+        };
+        let nibbles = digits.chars().map(nibble).collect::<Result<Vec<_>, _>>()?;
+
+        match nibbles[..] {
+            [r, g, b] => Ok(Color::init(r * 0x11, g * 0x11, b * 0x11)),
+            [r1, r0, g1, g0, b1, b0] => Ok(Color::init(r1 << 4 | r0, g1 << 4 | g0, b1 << 4 | b0)),
+            _ => Err(ParseColorError::InvalidLength(nibbles.len())),
+        }
+    }
+
+    /// Formats the color as `"#RRGGBB"`.
+    pub fn to_hex(&self) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        let mut hex = String::with_capacity(7);
+        hex.push('#');
+        for channel in [self.r, self.g, self.b] {
+            hex.push(DIGITS[usize::from(channel >> 4)] as char);
+            hex.push(DIGITS[usize::from(channel & 0xf)] as char);
+        }
+        hex
+    }
+}
+
+/// Color for strips with a dedicated white led (e.g. SK6812).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RGBWColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub w: u8,
+}
+
+impl RGBWColor {
+    pub const fn as_raw(&self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.w]
+    }
+}
+
+impl From<Color> for RGBWColor {
+    /// Moves the common part of all channels to the white channel.
+    fn from(value: Color) -> Self {
+        let w = value.r.min(value.g).min(value.b);
+        Self {
+            r: value.r - w,
+            g: value.g - w,
+            b: value.b - w,
+            w,
+        }
+    }
+}
+
 impl ColorExt for Color {
     fn with_transparency(self, transparency: f32) -> TransparentColor<Self> {
         TransparentColor::new(self, transparency)
@@ -518,12 +1199,43 @@ impl ColorExt for Color {
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawHSVColor")
+)]
 pub struct HSVColor {
     pub h: u16,
     pub s: u8,
     pub v: u8,
 }
 
+/// Unchecked [HSVColor], which is validated while deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawHSVColor {
+    h: u16,
+    s: u8,
+    v: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawHSVColor> for HSVColor {
+    type Error = &'static str;
+
+    fn try_from(raw: RawHSVColor) -> Result<Self, Self::Error> {
+        if raw.h > 360 {
+            Err("hue must be in range 0..=360")
+        } else if raw.s > 100 {
+            Err("saturation must be in range 0..=100")
+        } else if raw.v > 100 {
+            Err("value must be in range 0..=100")
+        } else {
+            Ok(Self::new(raw.h, raw.s, raw.v))
+        }
+    }
+}
+
 impl HSVColor {
     pub const fn new(h: u16, s: u8, v: u8) -> Self {
         assert!(h <= 360, "hue must be in range 0..=360");
@@ -564,6 +1276,70 @@ impl HSVColor {
         }
     }
 
+    pub const fn cyan() -> Self {
+        Self {
+            h: 180,
+            s: 100,
+            v: 100,
+        }
+    }
+
+    pub const fn magenta() -> Self {
+        Self {
+            h: 300,
+            s: 100,
+            v: 100,
+        }
+    }
+
+    pub const fn orange() -> Self {
+        Self {
+            h: 30,
+            s: 100,
+            v: 100,
+        }
+    }
+
+    pub const fn purple() -> Self {
+        Self {
+            h: 300,
+            s: 100,
+            v: 50,
+        }
+    }
+
+    pub const fn white() -> Self {
+        Self { h: 0, s: 0, v: 100 }
+    }
+
+    pub const fn black() -> Self {
+        Self { h: 0, s: 0, v: 0 }
+    }
+
+    /// Creates a color from normalized channels (0.0 - 1.0), values out of range are clamped.
+    pub fn from_norm(h: f32, s: f32, v: f32) -> Self {
+        Self {
+            h: (h.clamp(0.0, 1.0) * 360.0).round() as u16,
+            s: (s.clamp(0.0, 1.0) * 100.0).round() as u8,
+            v: (v.clamp(0.0, 1.0) * 100.0).round() as u8,
+        }
+    }
+
+    /// Hue normalized to 0.0 - 1.0.
+    pub fn h_norm(&self) -> f32 {
+        self.h as f32 / 360.0
+    }
+
+    /// Saturation normalized to 0.0 - 1.0.
+    pub fn s_norm(&self) -> f32 {
+        self.s as f32 / 100.0
+    }
+
+    /// Value normalized to 0.0 - 1.0.
+    pub fn v_norm(&self) -> f32 {
+        self.v as f32 / 100.0
+    }
+
     pub fn off_from_color(color: Color) -> Self {
         let hsv = Self::from(color);
         Self {
@@ -574,7 +1350,7 @@ impl HSVColor {
     }
 
     pub fn darken(self, amount: u8) -> Self {
-        let new_v = self.v.sub(amount);
+        let new_v = self.v.saturating_sub(amount);
         Self {
             h: self.h,
             s: self.s,
@@ -583,7 +1359,7 @@ impl HSVColor {
     }
 
     pub fn brighten(self, amount: u8) -> Self {
-        let new_v = max(self.v.add(amount), 100);
+        let new_v = min(self.v.saturating_add(amount), 100);
         Self {
             h: self.h,
             s: self.s,
@@ -591,11 +1367,81 @@ impl HSVColor {
         }
     }
 
+    /// Fully saturated and bright color with a hue derived from `led_id` and `seed`.
+    ///
+    /// The same id and seed always give the same color, e.g. for a reproducible speckle.
+    pub fn from_hash(led_id: LedId, seed: u32) -> Self {
+        let hash = hash32(u32::from(led_id) ^ seed.rotate_left(16));
+        Self::new((hash % 360) as u16, 100, 100)
+    }
+
+    /// Creates a color from an 8 bit hue (0..=255 spans the full circle), as used by e.g.
+    /// NeoPixel libraries.
+    pub fn from_hue8(h8: u8, s: u8, v: u8) -> Self {
+        let h = (u32::from(h8) * 360 + 128) / 256;
+        Self::new(h as u16, s, v)
+    }
+
+    /// Hue as 8 bit value, see [HSVColor::from_hue8].
+    pub fn to_hue8(&self) -> u8 {
+        ((u32::from(self.h) * 256 + 180) / 360 % 256) as u8
+    }
+
+    /// Like [HSVColor::brighten], but also lowers the saturation the closer the value gets to 100,
+    /// which resembles how bright leds are perceived.
+    pub fn brighten_perceptual(self, amount: u8) -> Self {
+        let new_v = self.v.saturating_add(amount).min(100);
+        let desaturation = u16::from(amount) * u16::from(new_v) / 400;
+        Self {
+            h: self.h,
+            s: self.s.saturating_sub(desaturation.min(255) as u8),
+            v: new_v,
+        }
+    }
+
+    /// Color on the opposite side of the color wheel (hue + 180), saturation and value are kept.
+    pub fn complementary(self) -> Self {
+        Self {
+            h: (self.h + 180) % 360,
+            ..self
+        }
+    }
+
+    /// The two neighbours `hue - spread` and `hue + spread` on the color wheel, saturation and
+    /// value are kept.
+    pub fn analogous(self, spread: u16) -> [Self; 2] {
+        let spread = spread % 360;
+        [
+            Self {
+                h: (self.h + 360 - spread) % 360,
+                ..self
+            },
+            Self {
+                h: (self.h + spread) % 360,
+                ..self
+            },
+        ]
+    }
+
+    /// Complementary hue (hue + 180) with inverted value (100 - value), the saturation is kept.
+    ///
+    /// Unlike [Color::inverted] this is not the RGB complement, e.g. a fully bright red becomes a
+    /// dark cyan.
+    pub fn inverted(&self) -> Self {
+        Self {
+            v: 100 - self.v,
+            ..self.complementary()
+        }
+    }
+
     pub const fn with_transparency(self, transparency: f32) -> TransparentColor<Self> {
         TransparentColor::new(self, transparency)
     }
 }
 
+/// The conversion rounds to the integer channels of [HSVColor], so a [Color] round trip drifts
+/// at most 1 per channel for grays. Converting the result again is stable and doesn't accumulate
+/// further error. Use [HSVColorF] for a lossless round trip.
 impl From<Color> for HSVColor {
     fn from(c: Color) -> Self {
         let (h, s, v) = rgb_to_hsv(c.r, c.g, c.b);
@@ -620,14 +1466,8 @@ impl CanTween for HSVColor {
             } else if on_off_fade {
                 from.h
             } else {
-                wrap_on(
-                    (from.h as i16
-                        + time
-                            .mul(num_traits::NumCast::from(to.h as i16 - from.h as i16).unwrap())
-                            .to_i16()
-                            .unwrap()) as u16,
-                    360,
-                )
+                // take the shorter arc around the color wheel, e.g. 350 -> 10 passes 0
+                lerp_hue_shortest(from.h, to.h, time)
             },
             if off_on_fade {
                 to.s
@@ -665,6 +1505,10 @@ impl Spectrum for HSVColor {
     fn is_transparent(&self) -> bool {
         false
     }
+
+    fn is_constant(&self) -> bool {
+        true
+    }
 }
 
 impl ColorExt for HSVColor {
@@ -682,6 +1526,116 @@ impl ColorExt for HSVColor {
     fn brighten(self, amount: u8) -> Self {
         self.brighten(amount)
     }
+
+    /// Alias of [HSVColor::inverted], which inverts in HSV instead of going through RGB.
+    fn invert(self) -> Self {
+        self.inverted()
+    }
+}
+
+/// HSV color with floating point channels (h: 0.0 - 360.0, s and v: 0.0 - 100.0).
+///
+/// Eases without quantization, so long and slow gradients don't band. Convert to [HSVColor] or
+/// [Color] only at output time.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HSVColorF {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+impl HSVColorF {
+    pub fn new(h: f32, s: f32, v: f32) -> Self {
+        assert!((0.0..=360.0).contains(&h), "hue must be in range 0..=360");
+        assert!(
+            (0.0..=100.0).contains(&s),
+            "saturation must be in range 0..=100"
+        );
+        assert!((0.0..=100.0).contains(&v), "value must be in range 0..=100");
+        Self { h, s, v }
+    }
+
+    pub fn with_transparency(self, transparency: f32) -> TransparentColor<Self> {
+        TransparentColor::new(self, transparency)
+    }
+}
+
+impl From<HSVColor> for HSVColorF {
+    fn from(c: HSVColor) -> Self {
+        Self {
+            h: c.h as f32,
+            s: c.s as f32,
+            v: c.v as f32,
+        }
+    }
+}
+
+impl From<HSVColorF> for HSVColor {
+    fn from(c: HSVColorF) -> Self {
+        Self {
+            h: (c.h.round() as u16).min(360),
+            s: (c.s.round() as u8).min(100),
+            v: (c.v.round() as u8).min(100),
+        }
+    }
+}
+
+impl From<Color> for HSVColorF {
+    fn from(c: Color) -> Self {
+        let (h, s, v) = rgb_to_hsv_f32(c.r, c.g, c.b);
+        Self { h, s, v }
+    }
+}
+
+impl From<HSVColorF> for Color {
+    fn from(c: HSVColorF) -> Self {
+        let (r, g, b) = hsv_f32_to_rgb(c.h, c.s, c.v);
+        Self { r, g, b }
+    }
+}
+
+impl From<HSVColorF> for TransparentColor<HSVColorF> {
+    fn from(value: HSVColorF) -> Self {
+        Self::new(value, 0.0)
+    }
+}
+
+impl CanTween for HSVColorF {
+    fn ease(from: Self, to: Self, time: impl num_traits::Float) -> Self {
+        let time = time.to_f32().unwrap();
+        let lerp = |from: f32, to: f32| from + (to - from) * time;
+        let off_on_fade = from.v == 0.0 && to.v > 0.0;
+        let on_off_fade = to.v == 0.0 && from.v > 0.0;
+        let (h, s) = if off_on_fade {
+            (to.h, to.s)
+        } else if on_off_fade {
+            (from.h, from.s)
+        } else {
+            let h = lerp(from.h, to.h);
+            (if h > 360.0 { h - 360.0 } else { h }, lerp(from.s, to.s))
+        };
+        Self {
+            h,
+            s,
+            v: lerp(from.v, to.v),
+        }
+    }
+}
+
+impl Spectrum for HSVColorF {
+    type Color = HSVColorF;
+
+    fn color_at(&self, _: f32) -> TransparentColor<Self::Color> {
+        (*self).into()
+    }
+
+    fn is_transparent(&self) -> bool {
+        false
+    }
+
+    fn is_constant(&self) -> bool {
+        true
+    }
 }
 
 #[derive(Debug)]
@@ -696,7 +1650,22 @@ impl<C> LedColoring<C> {
     }
 }
 
+/// Cheap integer hash with good avalanche (lowbias32).
+fn hash32(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^ (x >> 16)
+}
+
 fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
+    let (h, s, v) = rgb_to_hsv_f32(r, g, b);
+    // hues just below 360 round up to 360, which is the same as 0
+    (h.round() as u16 % 360, s.round() as u8, v.round() as u8)
+}
+
+fn rgb_to_hsv_f32(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
     let r = (r as f32) / 255f32;
     let g = (g as f32) / 255f32;
     let b = (b as f32) / 255f32;
@@ -708,7 +1677,7 @@ fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
         * if diff.is_zero() {
             0f32
         } else if c_max.eq(&r) {
-            ((g - b) / diff) % 6f32
+            (g - b) / diff
         } else if c_max.eq(&g) {
             ((b - r) / diff) + 2f32
         } else {
@@ -719,25 +1688,40 @@ fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (u16, u8, u8) {
 
     let v = c_max;
 
-    (h as u16, (s * 100f32) as u8, (v * 100f32) as u8)
+    (wrap_hue(h), s * 100f32, v * 100f32)
 }
 
 fn hsv_to_rgb(h: u16, s: u8, v: u8) -> (u8, u8, u8) {
-    let s = s as f32 / 100f32;
-    let v = v as f32 / 100f32;
+    hsv_f32_to_rgb(h as f32, s as f32, v as f32)
+}
+
+/// Wraps a hue in degrees into `0.0..360.0`.
+fn wrap_hue(h: f32) -> f32 {
+    let h = h % 360f32;
+    if h < 0f32 {
+        h + 360f32
+    } else {
+        h
+    }
+}
+
+fn hsv_f32_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+    let h = wrap_hue(h);
+    let s = s / 100f32;
+    let v = v / 100f32;
     let c = v * s;
-    let x = c * (1f32 - ((h as f32 / 60f32) % 2f32 - 1f32).abs());
+    let x = c * (1f32 - ((h / 60f32) % 2f32 - 1f32).abs());
     let m = v - c;
 
-    let (r, g, b) = if h < 60 {
+    let (r, g, b) = if h < 60f32 {
         (c, x, 0f32)
-    } else if h < 120 {
+    } else if h < 120f32 {
         (x, c, 0f32)
-    } else if h < 180 {
+    } else if h < 180f32 {
         (0f32, c, x)
-    } else if h < 240 {
+    } else if h < 240f32 {
         (0f32, x, c)
-    } else if h < 300 {
+    } else if h < 300f32 {
         (x, 0f32, c)
     } else {
         (c, 0f32, x)
@@ -754,9 +1738,9 @@ fn hsv_to_rgb(h: u16, s: u8, v: u8) -> (u8, u8, u8) {
     //     _ => panic!("hue must be 0 < h <= 360"),
     // };
 
-    let r = ((r + m) * 255f32) as u8;
-    let g = ((g + m) * 255f32) as u8;
-    let b = ((b + m) * 255f32) as u8;
+    let r = ((r + m) * 255f32).round() as u8;
+    let g = ((g + m) * 255f32).round() as u8;
+    let b = ((b + m) * 255f32).round() as u8;
 
     (r, g, b)
 }
@@ -877,6 +1861,21 @@ mod test {
         assert_eq!(hsv, (240, 100, 100))
     }
 
+    #[test]
+    fn rgb_to_hsv_reddish() {
+        assert_eq!(rgb_to_hsv(255, 0, 1), (0, 100, 100));
+        assert_eq!(rgb_to_hsv(255, 0, 128), (330, 100, 100));
+        let (h, _, _) = rgb_to_hsv_f32(255, 0, 1);
+        assert!((359.0..360.0).contains(&h), "{}", h);
+    }
+
+    #[test]
+    fn hsv_to_rgb_above_360() {
+        assert_eq!(hsv_to_rgb(400, 100, 100), hsv_to_rgb(40, 100, 100));
+        assert_eq!(hsv_to_rgb(720, 100, 100), (255, 0, 0));
+        assert_eq!(hsv_f32_to_rgb(-60.0, 100.0, 100.0), (255, 0, 255));
+    }
+
     #[test]
     fn hsv_to_rgb_white() {
         let h = 0;
@@ -917,6 +1916,20 @@ mod test {
         assert_eq!(rgb, (0, 0, 255))
     }
 
+    #[test]
+    fn named_hsv_colors_to_rgb() {
+        assert_eq!(Color::from(HSVColor::red()), Color::init(255, 0, 0));
+        assert_eq!(Color::from(HSVColor::green()), Color::init(0, 255, 0));
+        assert_eq!(Color::from(HSVColor::blue()), Color::init(0, 0, 255));
+        assert_eq!(Color::from(HSVColor::yellow()), Color::init(255, 255, 0));
+        assert_eq!(Color::from(HSVColor::cyan()), Color::init(0, 255, 255));
+        assert_eq!(Color::from(HSVColor::magenta()), Color::init(255, 0, 255));
+        assert_eq!(Color::from(HSVColor::orange()), Color::init(255, 128, 0));
+        assert_eq!(Color::from(HSVColor::purple()), Color::init(128, 0, 128));
+        assert_eq!(Color::from(HSVColor::white()), Color::init(255, 255, 255));
+        assert_eq!(Color::from(HSVColor::black()), Color::init(0, 0, 0));
+    }
+
     #[test]
     fn hsv_to_rgb_360() {
         let h = 360;
@@ -971,6 +1984,117 @@ mod test {
         assert_eq!(HSVColor::new(0, 100, 50), end)
     }
 
+    #[test]
+    fn hsv_f_round_trip() {
+        let color = HSVColor::new(200, 50, 75);
+
+        assert_eq!(HSVColor::from(HSVColorF::from(color)), color);
+        assert_eq!(
+            HSVColor::from(HSVColorF::new(199.6, 50.4, 74.5)),
+            HSVColor::new(200, 50, 75)
+        );
+        assert_eq!(
+            Color::from(HSVColorF::new(120.0, 100.0, 100.0)),
+            Color::green()
+        );
+    }
+
+    #[test]
+    fn gray_round_trip_drift() {
+        for level in 0..=255u8 {
+            let gray = Color::init(level, level, level);
+
+            let round_trip = Color::from(HSVColor::from(gray));
+            assert!(
+                (round_trip[0] as i16 - level as i16).abs() <= 1,
+                "level: {}",
+                level
+            );
+            assert_eq!(Color::from(HSVColor::from(round_trip)), round_trip);
+
+            assert_eq!(Color::from(HSVColorF::from(gray)), gray);
+        }
+    }
+
+    #[test]
+    fn hsv_f_from_color_is_lossless() {
+        for color in [
+            Color::init(12, 200, 99),
+            Color::init(250, 3, 180),
+            Color::init(1, 2, 3),
+        ] {
+            assert_eq!(Color::from(HSVColorF::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn hsv_f_long_gradient_has_no_banding() {
+        let from = HSVColor::new(100, 100, 10);
+        let to = HSVColor::new(100, 100, 12);
+        let steps: usize = 100;
+
+        let mut int_values = alloc::vec::Vec::new();
+        let mut float_values = alloc::vec::Vec::new();
+        for step in 0..=steps {
+            let int_color =
+                ease_with_scaled_time(functions::Linear, from, to, step as f32, steps as f32);
+            let float_color = ease_with_scaled_time(
+                functions::Linear,
+                HSVColorF::from(from),
+                HSVColorF::from(to),
+                step as f32,
+                steps as f32,
+            );
+            int_values.push(int_color.v);
+            float_values.push(float_color.v);
+        }
+        int_values.dedup();
+        float_values.dedup();
+
+        assert!(int_values.len() <= 3);
+        assert_eq!(float_values.len(), steps + 1);
+        assert!(float_values.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*float_values.last().unwrap(), 12.0);
+    }
+
+    #[test]
+    fn hsv_f_spectrum() {
+        let color = HSVColorF::new(10.5, 20.0, 30.0);
+
+        assert_eq!(color.color_at(0.7), color.into());
+        assert!(!color.is_transparent());
+    }
+
+    #[test]
+    fn hsv_normalized_round_trip() {
+        let color = HSVColor::new(200, 50, 75);
+
+        assert_eq!(color.h_norm(), 200.0 / 360.0);
+        assert_eq!(color.s_norm(), 0.5);
+        assert_eq!(color.v_norm(), 0.75);
+        assert_eq!(
+            HSVColor::from_norm(color.h_norm(), color.s_norm(), color.v_norm()),
+            color
+        );
+
+        for h in 0..=360 {
+            let color = HSVColor::new(h, 100, 100);
+            assert_eq!(HSVColor::from_norm(color.h_norm(), 1.0, 1.0), color);
+        }
+    }
+
+    #[test]
+    fn hsv_from_norm_clamps() {
+        assert_eq!(
+            HSVColor::from_norm(-0.5, 1.5, 2.0),
+            HSVColor::new(0, 100, 100)
+        );
+        assert_eq!(
+            HSVColor::from_norm(1.2, -1.0, 0.5),
+            HSVColor::new(360, 0, 50)
+        );
+    }
+
     #[test]
     fn test_spectrum_peak() {
         let spectrum = PeakSpectrum {
@@ -1004,6 +2128,815 @@ mod test {
         assert_eq!(spectrum.color_at(1.0), HSVColor::new(100, 100, 100).into());
     }
 
+    #[test]
+    fn test_sensor_mapping() {
+        let spectrum =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(100, 100, 100));
+        let mapping = SensorMapping::new(spectrum, 10.0, 30.0);
+
+        assert_eq!(mapping.color_for(20.0), HSVColor::new(50, 100, 100).into());
+        assert_eq!(mapping.color_for(10.0), HSVColor::new(0, 100, 100).into());
+        assert_eq!(mapping.color_for(30.0), HSVColor::new(100, 100, 100).into());
+    }
+
+    #[test]
+    fn test_sensor_mapping_out_of_range() {
+        let spectrum =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(100, 100, 100));
+        let mapping = SensorMapping::new(spectrum, 10.0, 30.0);
+
+        assert_eq!(mapping.color_for(-5.0), HSVColor::new(0, 100, 100).into());
+        assert_eq!(
+            mapping.color_for(100.0),
+            HSVColor::new(100, 100, 100).into()
+        );
+    }
+
+    #[test]
+    fn test_spectrum_gamma() {
+        let color = HSVColor::new(100, 100, 50);
+
+        assert_eq!(color.gamma(1.0).color_at(0.5), color.into());
+        assert_eq!(
+            color.gamma(2.2).color_at(0.5),
+            HSVColor::new(100, 100, 22).into()
+        );
+        assert_eq!(
+            HSVColor::new(100, 100, 100).gamma(2.2).color_at(0.5),
+            HSVColor::new(100, 100, 100).into()
+        );
+    }
+
+    #[test]
+    fn test_blended_spectrum() {
+        let red_ramp = RainbowSpectrum::new(HSVColor::new(0, 100, 0), HSVColor::new(0, 100, 100));
+        let blue_ramp =
+            RainbowSpectrum::new(HSVColor::new(240, 100, 0), HSVColor::new(240, 100, 100));
+        let spectrum = blend_spectra(red_ramp, blue_ramp, 0.5);
+
+        assert_eq!(spectrum.color_at(1.0), HSVColor::new(120, 100, 100).into());
+        assert_eq!(spectrum.color_at(0.5), HSVColor::new(120, 100, 50).into());
+    }
+
+    #[test]
+    fn test_blended_spectrum_shared_ratio() {
+        let t = Rc::new(Cell::new(0.0));
+        let spectrum = BlendedSpectrum::shared(HSVColor::red(), HSVColor::blue(), t.clone());
+
+        assert_eq!(spectrum.color_at(0.5), HSVColor::red().into());
+        t.set(1.0);
+        assert_eq!(spectrum.color_at(0.5), HSVColor::blue().into());
+    }
+
+    #[test]
+    fn test_phased_spectrum_shifts_cyclically() {
+        let rainbow =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(300, 100, 100));
+        let phase = Rc::new(Cell::new(0.0));
+        let spectrum = PhasedSpectrum::new(rainbow, phase.clone());
+
+        assert_eq!(spectrum.color_at(0.5), rainbow.color_at(0.5));
+        assert_eq!(spectrum.color_at(1.0), rainbow.color_at(1.0));
+
+        phase.set(0.25);
+        assert_eq!(spectrum.color_at(0.0), rainbow.color_at(0.25));
+        assert_eq!(spectrum.color_at(0.5), rainbow.color_at(0.75));
+        assert_eq!(spectrum.color_at(0.875), rainbow.color_at(0.125));
+
+        phase.set(1.5);
+        assert_eq!(spectrum.color_at(0.25), rainbow.color_at(0.75));
+
+        phase.set(-0.25);
+        assert_eq!(spectrum.color_at(0.0), rainbow.color_at(0.75));
+        assert_eq!(spectrum.color_at(0.5), rainbow.color_at(0.25));
+    }
+
+    #[test]
+    fn test_color_inverted() {
+        assert_eq!(Color::white().inverted(), Color::off());
+        assert_eq!(
+            Color::init(10, 128, 255).inverted(),
+            Color::init(245, 127, 0)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let color = Color::init(10, 128, 255);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+
+        let hsv = HSVColor::new(270, 50, 30);
+        let json = serde_json::to_string(&hsv).unwrap();
+        assert_eq!(json, r#"{"h":270,"s":50,"v":30}"#);
+        assert_eq!(serde_json::from_str::<HSVColor>(&json).unwrap(), hsv);
+
+        let transparent = hsv.with_transparency(0.25);
+        let json = serde_json::to_string(&transparent).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TransparentColor<HSVColor>>(&json).unwrap(),
+            transparent
+        );
+
+        for mode in [
+            BlendMode::AllChannels,
+            BlendMode::ValueOnly,
+            BlendMode::HueOnly,
+            BlendMode::Multiply,
+        ] {
+            let json = serde_json::to_string(&mode).unwrap();
+            assert_eq!(serde_json::from_str::<BlendMode>(&json).unwrap(), mode);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_invalid_hsv() {
+        assert!(serde_json::from_str::<HSVColor>(r#"{"h":361,"s":50,"v":30}"#).is_err());
+        assert!(serde_json::from_str::<HSVColor>(r#"{"h":0,"s":101,"v":30}"#).is_err());
+        assert!(serde_json::from_str::<HSVColor>(r#"{"h":0,"s":50,"v":101}"#).is_err());
+    }
+
+    #[test]
+    fn test_color_ext_invert() {
+        assert_eq!(ColorExt::invert(Color::white()), Color::off());
+        for color in [
+            Color::off(),
+            Color::red(),
+            Color::init(10, 128, 255),
+            Color::init(1, 2, 3),
+        ] {
+            assert_eq!(ColorExt::invert(ColorExt::invert(color)), color);
+        }
+
+        assert_eq!(
+            ColorExt::invert(HSVColor::new(0, 100, 40)),
+            HSVColor::new(180, 100, 60)
+        );
+    }
+
+    #[test]
+    fn test_hsv_inverted() {
+        assert_eq!(
+            HSVColor::new(0, 100, 40).inverted(),
+            HSVColor::new(180, 100, 60)
+        );
+        assert_eq!(HSVColor::red().inverted(), HSVColor::new(180, 100, 0));
+        assert_eq!(
+            HSVColor::new(270, 50, 30).inverted(),
+            HSVColor::new(90, 50, 70)
+        );
+    }
+
+    #[test]
+    fn test_hsv_ease_takes_shortest_hue_path() {
+        let from = HSVColor::new(350, 100, 100);
+        let to = HSVColor::new(10, 100, 100);
+
+        assert_eq!(HSVColor::ease(from, to, 0.0), from);
+        assert_eq!(HSVColor::ease(from, to, 0.25), HSVColor::new(355, 100, 100));
+        assert_eq!(HSVColor::ease(from, to, 0.5), HSVColor::new(0, 100, 100));
+        assert_eq!(HSVColor::ease(from, to, 1.0), to);
+        assert_eq!(HSVColor::ease(to, from, 0.5), HSVColor::new(0, 100, 100));
+        assert_eq!(HSVColor::ease(to, from, 1.0), from);
+
+        // short distances are unchanged
+        assert_eq!(
+            HSVColor::ease(
+                HSVColor::new(0, 100, 100),
+                HSVColor::new(100, 100, 100),
+                0.5
+            ),
+            HSVColor::new(50, 100, 100)
+        );
+        // off/on fades keep the hue of the lit color
+        assert_eq!(
+            HSVColor::ease(HSVColor::new(350, 100, 0), to, 0.5),
+            HSVColor::new(10, 100, 50)
+        );
+    }
+
+    #[test]
+    fn test_shortest_arc_spectrum() {
+        let red = HSVColor::new(0, 100, 100);
+        let magenta = HSVColor::new(300, 100, 100);
+
+        let short = ShortestArcSpectrum::new(red, magenta);
+        assert_eq!(short.color_at(0.0), red.into());
+        assert_eq!(short.color_at(0.5), HSVColor::new(330, 100, 100).into());
+        assert_eq!(short.color_at(1.0), magenta.into());
+        assert_eq!(
+            RainbowSpectrum::new(red, magenta).color_at(0.5),
+            short.color_at(0.5)
+        );
+
+        let back = ShortestArcSpectrum::new(magenta, HSVColor::new(20, 100, 100));
+        assert_eq!(back.color_at(0.5), HSVColor::new(340, 100, 100).into());
+        assert_eq!(back.color_at(0.75), HSVColor::new(0, 100, 100).into());
+    }
+
+    #[test]
+    fn test_reversed_spectrum() {
+        let rainbow =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(100, 100, 100));
+        let reversed = rainbow.reversed();
+
+        assert_eq!(reversed.color_at(0.25), rainbow.color_at(0.75));
+        assert_eq!(reversed.color_at(0.0), rainbow.last_color());
+        assert_eq!(reversed.last_color(), rainbow.first_color());
+
+        let gradient = GradientSpectrum::new(alloc::vec![
+            (0.0, HSVColor::new(0, 100, 100).into()),
+            (0.5, HSVColor::new(0, 0, 100).with_transparency(0.5)),
+        ]);
+        assert_eq!(
+            gradient.clone().reversed().color_at(0.75),
+            gradient.color_at(0.25)
+        );
+        assert!(gradient.reversed().is_transparent());
+    }
+
+    #[test]
+    fn test_shared_darkened_spectrum() {
+        let amount = Rc::new(Cell::new(0));
+        let spectrum = DarkenedSpectrum::shared(HSVColor::new(0, 100, 80), amount.clone());
+
+        assert_eq!(spectrum.color_at(0.5), HSVColor::new(0, 100, 80).into());
+        amount.set(30);
+        assert_eq!(spectrum.color_at(0.5), HSVColor::new(0, 100, 50).into());
+        amount.set(100);
+        assert_eq!(spectrum.color_at(0.5), HSVColor::new(0, 100, 0).into());
+
+        let fixed = SpectrumExt::darken(HSVColor::new(0, 100, 80), 30);
+        assert_eq!(fixed.color_at(0.5), HSVColor::new(0, 100, 50).into());
+    }
+
+    #[test]
+    fn test_concat_spectrum() {
+        let rainbow =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(100, 100, 100));
+        let blue = HSVColor::new(240, 100, 100);
+        let concat = rainbow.chain(blue, 0.5);
+
+        assert_eq!(concat.color_at(0.0), rainbow.first_color());
+        assert_eq!(concat.color_at(0.25), rainbow.color_at(0.5));
+        assert_eq!(concat.color_at(0.5), blue.into());
+        assert_eq!(concat.color_at(1.0), blue.into());
+        assert!(!concat.is_constant());
+
+        let only_b = rainbow.chain(blue, 0.0);
+        assert_eq!(only_b.color_at(0.0), blue.into());
+        assert!(only_b.is_constant());
+
+        let only_a = rainbow.chain(blue, 1.0);
+        assert_eq!(only_a.color_at(0.5), rainbow.color_at(0.5));
+        assert_eq!(only_a.color_at(1.0), rainbow.last_color());
+    }
+
+    #[test]
+    fn test_alpha8() {
+        let red = HSVColor::red();
+
+        assert_eq!(TransparentColor::from_alpha8(red, 255).transparency, 0.0);
+        assert_eq!(TransparentColor::from_alpha8(red, 0).transparency, 1.0);
+        assert_eq!(TransparentColor::opaque(red).alpha8(), 255);
+        assert_eq!(red.with_transparency(1.0).alpha8(), 0);
+        assert_eq!(red.with_transparency(0.5).alpha8(), 128);
+        assert_eq!(red.with_transparency(2.0).alpha8(), 0);
+
+        for alpha in [0, 1, 64, 127, 128, 200, 254, 255] {
+            assert_eq!(TransparentColor::from_alpha8(red, alpha).alpha8(), alpha);
+        }
+    }
+
+    #[test]
+    fn test_sample_into() {
+        let rainbow =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(200, 100, 100));
+        let mut buffer = [TransparentColor::opaque(HSVColor::default()); 5];
+
+        rainbow.sample_into(&mut buffer);
+
+        for (i, color) in buffer.iter().enumerate() {
+            assert_eq!(*color, rainbow.color_at(i as f32 / 4.0), "slot: {}", i);
+        }
+        assert_eq!(buffer[0], rainbow.first_color());
+        assert_eq!(buffer[4], rainbow.last_color());
+
+        let mut single = [TransparentColor::opaque(HSVColor::default()); 1];
+        rainbow.sample_into(&mut single);
+        assert_eq!(single[0], rainbow.first_color());
+    }
+
+    #[test]
+    fn test_color_at_rgb() {
+        let gradient = GradientSpectrum::new(alloc::vec![
+            (0.0, HSVColor::new(0, 100, 100).into()),
+            (1.0, HSVColor::new(120, 100, 50).with_transparency(0.5)),
+        ]);
+
+        for p in [0.0, 0.3, 0.5, 1.0] {
+            let c = gradient.color_at(p);
+            assert_eq!(gradient.color_at_rgb(p), (c.color.into(), c.transparency));
+        }
+        assert_eq!(gradient.color_at_rgb(0.0), (Color::init(255, 0, 0), 0.0));
+    }
+
+    #[test]
+    fn test_tiled_spectrum() {
+        let rainbow =
+            RainbowSpectrum::new(HSVColor::new(0, 100, 100), HSVColor::new(100, 100, 100));
+        let tiled = rainbow.tiled(2.0);
+
+        assert_eq!(tiled.color_at(0.0), tiled.color_at(0.5));
+        assert_eq!(tiled.color_at(0.0), rainbow.first_color());
+        assert_eq!(tiled.color_at(0.25), rainbow.color_at(0.5));
+        assert_eq!(tiled.color_at(0.75), rainbow.color_at(0.5));
+        assert_eq!(tiled.color_at(1.0), rainbow.last_color());
+
+        let cut = rainbow.tiled(2.5);
+        assert_eq!(cut.color_at(0.0), rainbow.first_color());
+        assert_eq!(cut.color_at(1.0), rainbow.color_at(0.5));
+    }
+
+    #[test]
+    fn test_gradient_spectrum() {
+        let red = HSVColor::new(0, 100, 100);
+        let white = HSVColor::new(0, 0, 100);
+        let blue = HSVColor::new(240, 100, 100);
+        let flag = GradientSpectrum::new(alloc::vec![
+            (1.0, blue.into()),
+            (0.0, red.into()),
+            (0.5, white.into()),
+        ]);
+
+        assert_eq!(flag.color_at(0.0), red.into());
+        assert_eq!(flag.color_at(0.25), HSVColor::new(0, 50, 100).into());
+        assert_eq!(flag.color_at(0.5), white.into());
+        assert_eq!(flag.color_at(1.0), blue.into());
+        assert!(!flag.is_constant());
+
+        let inset = GradientSpectrum::new(alloc::vec![(0.25, red.into()), (0.75, blue.into())]);
+        assert_eq!(inset.color_at(0.1), red.into());
+        assert_eq!(inset.color_at(0.9), blue.into());
+
+        let single = GradientSpectrum::new(alloc::vec![(0.5, white.into())]);
+        assert!(single.is_constant());
+        assert_eq!(single.color_at(0.0), white.into());
+        assert_eq!(single.color_at(1.0), white.into());
+    }
+
+    #[test]
+    fn test_spectra_at_full_percentage() {
+        let red = HSVColor::new(0, 100, 100);
+        let blue = HSVColor::new(240, 100, 100);
+
+        assert_eq!(RainbowSpectrum::new(red, blue).color_at(1.0), blue.into());
+        assert_eq!(RainbowSpectrum::new(red, blue).color_at(1.5), blue.into());
+        assert_eq!(RainbowSpectrum::new(red, blue).color_at(-0.5), red.into());
+        assert_eq!(
+            RainbowSpectrum::new(red, blue).color_at(f32::NAN),
+            red.into()
+        );
+
+        let peak = PeakSpectrum {
+            from_color: red.into(),
+            peak_color: blue.into(),
+            curve: Curve::Linear,
+        };
+        assert_eq!(peak.color_at(1.0), red.into());
+        assert_eq!(peak.color_at(2.0), red.into());
+        assert_eq!(peak.last_color(), red.into());
+
+        let green = HSVColor::new(120, 100, 100);
+        let rainbow = RainbowSpectrum::new(red, blue);
+        assert_eq!(
+            ShortestArcSpectrum::new(red, blue).color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(rainbow.reversed().color_at(1.0), red.into());
+        assert_eq!(rainbow.tiled(2.0).color_at(1.0), blue.into());
+        assert_eq!(rainbow.gamma(2.2).color_at(1.0), blue.into());
+        assert_eq!(
+            rainbow.darken(20).color_at(1.0),
+            ColorExt::darken(blue, 20).into()
+        );
+        assert_eq!(
+            RainbowSpectrum::new(red, green)
+                .chain(RainbowSpectrum::new(green, blue), 0.5)
+                .color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            PhasedSpectrum::new(rainbow, Rc::new(Cell::new(0.0))).color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            BlendedSpectrum::new(rainbow, RainbowSpectrum::new(blue, red), 0.0).color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            GradientSpectrum::new(alloc::vec![
+                (0.0, red.into()),
+                (0.5, green.into()),
+                (1.0, blue.into()),
+            ])
+            .color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            crate::keyframed::Keyframed::new(alloc::vec![(0.0, red.into()), (1.0, blue.into())])
+                .color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            crate::pattern::PatternAsSpectrum::new(rainbow, 5).color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            TransparentColor::opaque(red)
+                .map(|p, c, _| c.h = (p * 240.0) as u16)
+                .color_at(1.0),
+            blue.into()
+        );
+        assert_eq!(
+            blue.with_transparency(0.5).color_at(1.0),
+            blue.with_transparency(0.5)
+        );
+        assert_eq!(blue.color_at(1.0), blue.into());
+        assert_eq!(
+            HSVColorF::from(blue).color_at(1.0),
+            TransparentColor::opaque(HSVColorF::from(blue))
+        );
+    }
+
+    #[test]
+    fn test_color_from_hex() {
+        assert_eq!(Color::from_hex("#FF8000"), Ok(Color::init(255, 128, 0)));
+        assert_eq!(Color::from_hex("ff8000"), Ok(Color::init(255, 128, 0)));
+        assert_eq!(Color::from_hex("#f80"), Ok(Color::init(255, 136, 0)));
+        assert_eq!(Color::from_hex("0a1"), Ok(Color::init(0, 170, 17)));
+    }
+
+    #[test]
+    fn test_color_from_hex_malformed() {
+        assert_eq!(
+            Color::from_hex("#FF80"),
+            Err(ParseColorError::InvalidLength(4))
+        );
+        assert_eq!(Color::from_hex(""), Err(ParseColorError::InvalidLength(0)));
+        assert_eq!(Color::from_hex("#"), Err(ParseColorError::InvalidLength(0)));
+        assert_eq!(
+            Color::from_hex("#GG0000"),
+            Err(ParseColorError::InvalidDigit('G'))
+        );
+        assert_eq!(
+            Color::from_hex("##FFF"),
+            Err(ParseColorError::InvalidDigit('#'))
+        );
+    }
+
+    #[test]
+    fn test_color_hex_round_trip() {
+        assert_eq!(Color::init(255, 128, 0).to_hex(), "#FF8000");
+        assert_eq!(Color::off().to_hex(), "#000000");
+        for color in [
+            Color::init(1, 2, 3),
+            Color::init(171, 205, 239),
+            Color::white(),
+        ] {
+            assert_eq!(Color::from_hex(&color.to_hex()), Ok(color));
+        }
+    }
+
+    #[test]
+    fn test_rgbw_from_color() {
+        let white = RGBWColor::from(Color::white());
+        assert_eq!(white.as_raw(), [0, 0, 0, 255]);
+
+        assert_eq!(RGBWColor::from(Color::red()).as_raw(), [255, 0, 0, 0]);
+        assert_eq!(RGBWColor::from(Color::init(255, 0, 255)).w, 0);
+        assert_eq!(
+            RGBWColor::from(Color::init(200, 100, 50)).as_raw(),
+            [150, 50, 0, 50]
+        );
+        assert_eq!(RGBWColor::from(Color::off()), RGBWColor::default());
+    }
+
+    #[test]
+    fn test_gamma_correct() {
+        assert_eq!(Color::off().gamma_2_2(), Color::off());
+        assert_eq!(Color::white().gamma_2_2(), Color::white());
+        assert_eq!(
+            Color::init(128, 64, 255).gamma_2_2(),
+            Color::init(56, 12, 255)
+        );
+        assert_eq!(
+            Color::init(128, 64, 255).gamma_correct(1.0),
+            Color::init(128, 64, 255)
+        );
+    }
+
+    #[test]
+    fn test_luma_and_grayscale() {
+        assert_eq!(Color::red().luma(), 54);
+        assert_eq!(Color::green().luma(), 182);
+        assert_eq!(Color::blue().luma(), 18);
+        assert_eq!(Color::white().luma(), 255);
+        assert_eq!(Color::off().luma(), 0);
+
+        assert_eq!(Color::green().grayscale(), Color::init(182, 182, 182));
+        assert_eq!(Color::white().grayscale(), Color::white());
+    }
+
+    #[test]
+    fn test_color_add_saturates() {
+        assert_eq!(Color::red() + Color::red(), Color::red());
+        assert_eq!(
+            Color::init(200, 100, 250) + Color::init(100, 100, 10),
+            Color::init(255, 200, 255)
+        );
+        assert_eq!(
+            Color::init(10, 255, 1) + Color::init(0, 1, 255),
+            Color::init(10, 255, 255)
+        );
+    }
+
+    #[test]
+    fn test_color_sub_saturates() {
+        assert_eq!(Color::off() - Color::white(), Color::off());
+        assert_eq!(
+            Color::init(100, 10, 50) - Color::init(200, 5, 50),
+            Color::init(0, 5, 0)
+        );
+        assert_eq!(
+            Color::init(1, 0, 255) - Color::init(0, 1, 0),
+            Color::init(1, 0, 255)
+        );
+    }
+
+    #[test]
+    fn test_color_tween_lands_on_endpoints() {
+        let from = Color::init(255, 0, 100);
+        let to = Color::init(0, 255, 100);
+
+        assert_eq!(Color::ease(from, to, 0.0), from);
+        assert_eq!(Color::ease(from, to, 1.0), to);
+        assert_eq!(Color::ease(from, to, 0.5), Color::init(128, 128, 100));
+        assert_eq!(Color::ease(to, from, 1.0), from);
+    }
+
+    #[test]
+    fn test_transparent_color_over() {
+        let red = HSVColor::new(0, 100, 100);
+        let half_blue = TransparentColor::new(HSVColor::new(240, 100, 100), 0.5);
+
+        assert_eq!(
+            half_blue.over(red, BlendMode::AllChannels),
+            blend_colors(red, half_blue, BlendMode::AllChannels)
+        );
+        assert_eq!(
+            half_blue.over(red, BlendMode::AllChannels),
+            HSVColor::new(120, 100, 100)
+        );
+        assert_eq!(
+            half_blue.over(HSVColor::new(0, 100, 50), BlendMode::ValueOnly),
+            HSVColor::new(240, 100, 75)
+        );
+    }
+
+    #[test]
+    fn test_blend_hue_and_saturation_only() {
+        let base = HSVColor::new(350, 80, 60);
+        let blend = HSVColor::new(30, 20, 100);
+
+        assert_eq!(
+            blend_colors(base, blend.with_transparency(0.5), BlendMode::HueOnly),
+            HSVColor::new(10, 80, 60)
+        );
+        assert_eq!(
+            blend_colors(base, blend.into(), BlendMode::HueOnly),
+            HSVColor::new(30, 80, 60)
+        );
+        assert_eq!(
+            blend_colors(
+                base,
+                blend.with_transparency(0.5),
+                BlendMode::SaturationOnly
+            ),
+            HSVColor::new(350, 50, 60)
+        );
+        assert_eq!(
+            blend_colors(base, blend.into(), BlendMode::SaturationOnly),
+            HSVColor::new(350, 20, 60)
+        );
+        assert_eq!(
+            blend_colors(base, blend.with_transparency(1.0), BlendMode::HueOnly),
+            base
+        );
+    }
+
+    #[test]
+    fn test_blend_premultiplied() {
+        // straight alpha over operator, `out_a = sa + da (1 - sa)`,
+        // `out_c = (sc sa + dc da (1 - sa)) / out_a`
+        let over = |dst: (f32, [f32; 3]), src: (f32, [f32; 3])| {
+            let a = src.0 + dst.0 * (1.0 - src.0);
+            let c = [0, 1, 2].map(|i| (src.1[i] * src.0 + dst.1[i] * dst.0 * (1.0 - src.0)) / a);
+            (a, c)
+        };
+        let red = (0.5, [255.0, 0.0, 0.0]);
+        let green = (0.5, [0.0, 255.0, 0.0]);
+        let blue = (1.0, [0.0, 0.0, 255.0]);
+        let (alpha, expected) = over(blue, over(green, red));
+
+        let layered = blend_premultiplied(
+            Color::blue().into(),
+            blend_premultiplied(
+                Color::green().with_transparency(0.5),
+                Color::red().with_transparency(0.5),
+            ),
+        );
+
+        assert_eq!(layered.transparency, 1.0 - alpha);
+        for channel in 0..3 {
+            assert_eq!(
+                layered.color[channel],
+                expected[channel as usize].round() as u8
+            );
+        }
+        assert_eq!(layered.color, Color::init(128, 64, 64));
+
+        let red_over_green = blend_premultiplied(
+            Color::green().with_transparency(0.5),
+            Color::red().with_transparency(0.5),
+        );
+        assert_eq!(red_over_green.color, Color::init(170, 85, 0));
+        assert_eq!(red_over_green.transparency, 0.25);
+
+        let transparent = Color::off().with_transparency(1.0);
+        assert_eq!(blend_premultiplied(transparent, transparent), transparent);
+    }
+
+    #[test]
+    fn test_blend_screen_and_multiply() {
+        let base = HSVColor::new(30, 100, 100);
+        let opaque = TransparentColor::opaque;
+
+        assert_eq!(
+            blend_colors(base, opaque(HSVColor::new(0, 0, 100)), BlendMode::Multiply),
+            base
+        );
+        assert_eq!(
+            blend_colors(base, opaque(HSVColor::new(0, 0, 0)), BlendMode::Screen),
+            base
+        );
+
+        let red = HSVColor::new(0, 100, 100);
+        let blue = HSVColor::new(240, 100, 100);
+        assert_eq!(
+            Color::from(blend_colors(red, opaque(blue), BlendMode::Screen)),
+            Color::init(255, 0, 255)
+        );
+        assert_eq!(
+            Color::from(blend_colors(red, opaque(blue), BlendMode::Multiply)),
+            Color::off()
+        );
+        assert_eq!(
+            blend_colors(red, TransparentColor::new(blue, 1.0), BlendMode::Multiply),
+            red
+        );
+    }
+
+    #[test]
+    fn test_complementary_and_analogous() {
+        assert_eq!(HSVColor::red().complementary(), HSVColor::cyan());
+        assert_eq!(
+            HSVColor::new(270, 50, 40).complementary(),
+            HSVColor::new(90, 50, 40)
+        );
+
+        assert_eq!(
+            HSVColor::new(120, 80, 60).analogous(30),
+            [HSVColor::new(90, 80, 60), HSVColor::new(150, 80, 60)]
+        );
+        assert_eq!(
+            HSVColor::new(10, 100, 100).analogous(30),
+            [HSVColor::new(340, 100, 100), HSVColor::new(40, 100, 100)]
+        );
+        assert_eq!(
+            HSVColor::new(350, 100, 100).analogous(30),
+            [HSVColor::new(320, 100, 100), HSVColor::new(20, 100, 100)]
+        );
+        assert_eq!(
+            HSVColor::new(0, 100, 100).analogous(390),
+            HSVColor::new(0, 100, 100).analogous(30)
+        );
+    }
+
+    #[test]
+    fn test_from_hash_is_stable() {
+        assert_eq!(HSVColor::from_hash(3, 42), HSVColor::from_hash(3, 42));
+
+        let hues = (0..16)
+            .map(|led| HSVColor::from_hash(led, 42).h)
+            .collect::<alloc::vec::Vec<_>>();
+        let distinct = hues
+            .iter()
+            .enumerate()
+            .filter(|(i, h)| !hues[..*i].contains(h))
+            .count();
+        assert!(distinct > 12, "hues {:?} should mostly differ", hues);
+
+        assert_ne!(HSVColor::from_hash(3, 42), HSVColor::from_hash(3, 43));
+    }
+
+    #[test]
+    fn test_hue8() {
+        let close_to = |hsv: HSVColor, color: Color| {
+            let rgb = Color::from(hsv);
+            for channel in 0..3 {
+                assert!(
+                    rgb[channel].abs_diff(color[channel]) <= 8,
+                    "{:?} should be close to {:?}",
+                    rgb,
+                    color
+                );
+            }
+        };
+        close_to(HSVColor::from_hue8(0, 100, 100), Color::red());
+        close_to(HSVColor::from_hue8(85, 100, 100), Color::green());
+        close_to(HSVColor::from_hue8(170, 100, 100), Color::blue());
+
+        for h8 in [0, 1, 85, 128, 170, 255] {
+            assert_eq!(HSVColor::from_hue8(h8, 100, 100).to_hue8(), h8);
+        }
+        assert_eq!(HSVColor::new(360, 100, 100).to_hue8(), 0);
+    }
+
+    #[test]
+    fn test_brighten_clamps_at_100() {
+        assert_eq!(
+            HSVColor::new(10, 50, 80).brighten(50),
+            HSVColor::new(10, 50, 100)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 100).brighten(1),
+            HSVColor::new(10, 50, 100)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 100).brighten(255),
+            HSVColor::new(10, 50, 100)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 40).brighten(20),
+            HSVColor::new(10, 50, 60)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 40).brighten(0),
+            HSVColor::new(10, 50, 40)
+        );
+    }
+
+    #[test]
+    fn test_darken_floors_at_0() {
+        assert_eq!(
+            HSVColor::new(10, 50, 30).darken(50),
+            HSVColor::new(10, 50, 0)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 30).darken(255),
+            HSVColor::new(10, 50, 0)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 30).darken(30),
+            HSVColor::new(10, 50, 0)
+        );
+        assert_eq!(
+            HSVColor::new(10, 50, 30).darken(10),
+            HSVColor::new(10, 50, 20)
+        );
+    }
+
+    #[test]
+    fn test_brighten_perceptual() {
+        let saturated = HSVColor::new(120, 100, 60);
+        let brightened = saturated.brighten(20);
+        let perceptual = saturated.brighten_perceptual(20);
+        assert_eq!(perceptual, HSVColor::new(120, 96, 80));
+        assert_eq!(perceptual.h, brightened.h);
+        assert!(perceptual.s < brightened.s);
+
+        let desaturated = HSVColor::new(120, 2, 60);
+        let perceptual = desaturated.brighten_perceptual(20);
+        assert_eq!(perceptual, HSVColor::new(120, 0, 80));
+
+        let white = HSVColor::new(0, 0, 90);
+        assert_eq!(white.brighten_perceptual(40), HSVColor::new(0, 0, 100));
+        assert_eq!(white.brighten_perceptual(40).s, white.brighten(40).s);
+    }
+
     #[test]
     fn test_mix_colors() {
         let base_color = HSVColor::new(0, 100, 100);
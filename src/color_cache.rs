@@ -2,7 +2,11 @@ use core::ops::Range;
 
 use alloc::{boxed::Box, vec::Vec};
 
-use crate::{color::HSVColor, indexing::LedId};
+use crate::{
+    color::{Color, HSVColor},
+    indexing::LedId,
+    strip::Strip,
+};
 
 #[derive(Debug)]
 struct SingleColor {
@@ -201,6 +205,17 @@ impl ColorCache {
         }
     }
 
+    /// Writes every cached color back to its led and empties the cache.
+    pub fn restore_to<S: Strip>(&mut self, strip: &mut S) {
+        for single_cache in self.multi_color_cache.take().into_iter().flatten() {
+            let color = Color::from(single_cache.color);
+            let ranges = single_cache.ranges.iter().cloned().flatten();
+            for led_id in ranges.chain(single_cache.single_led.iter().copied()) {
+                strip.set_led_to_color(led_id, &color);
+            }
+        }
+    }
+
     // fn change_cache_strategy(&mut self) {}
 
     fn init(&mut self, color: &HSVColor) {
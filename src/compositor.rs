@@ -0,0 +1,167 @@
+use core::cell::RefCell;
+
+use alloc::{rc::Rc, vec::Vec};
+
+use crate::{color::Color, indexing::LedId, strip::Strip};
+
+/// In memory layer, which can be used as strip of an
+/// [AnimationController](crate::controller::AnimationController).
+///
+/// Only leds which were written are part of the layer, all other leds are transparent when
+/// composited.
+#[derive(Debug)]
+pub struct FrameBuffer<const N: usize> {
+    leds: [Option<Color>; N],
+}
+
+impl<const N: usize> FrameBuffer<N> {
+    pub fn new() -> Self {
+        Self { leds: [None; N] }
+    }
+
+    pub fn color_of_led(&self, led_id: LedId) -> Option<Color> {
+        self.leds[usize::from(led_id)]
+    }
+
+    /// Makes all leds of the layer transparent again.
+    pub fn clear(&mut self) {
+        self.leds = [None; N];
+    }
+}
+
+impl<const N: usize> Default for FrameBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Strip for FrameBuffer<N> {
+    const LED_AMOUNT: usize = N;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        self.leds[usize::from(led_id)] = Some(*color);
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {}
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.color_of_led(led_id).unwrap_or_default()
+    }
+}
+
+/// Composites the layers of several controllers onto one strip.
+///
+/// Layers are stacked in the order they were added, a later layer covers an earlier one. Leds
+/// which aren't written by any layer are left untouched on the strip.
+pub struct Compositor<S, const N: usize> {
+    strip: Rc<RefCell<S>>,
+    layers: Vec<Rc<RefCell<FrameBuffer<N>>>>,
+}
+
+impl<S: Strip, const N: usize> Compositor<S, N> {
+    pub fn new(strip: Rc<RefCell<S>>) -> Self {
+        assert_eq!(S::LED_AMOUNT, N, "layers must have the size of the strip");
+        Self {
+            strip,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Adds a new layer on top of all existing layers.
+    pub fn add_layer(&mut self) -> Rc<RefCell<FrameBuffer<N>>> {
+        let layer = Rc::new(RefCell::new(FrameBuffer::new()));
+        self.layers.push(layer.clone());
+        layer
+    }
+
+    /// Writes the composited layers to the strip. [Strip::update_leds] isn't called.
+    pub fn compose(&mut self) {
+        let mut strip = self.strip.borrow_mut();
+        for led_id in 0..N {
+            let led_id = LedId::try_from(led_id).unwrap();
+            let color = self
+                .layers
+                .iter()
+                .rev()
+                .find_map(|layer| layer.borrow().color_of_led(led_id));
+            if let Some(color) = color {
+                strip.set_led_to_color(led_id, &color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::StaticAnimation,
+        color::{BlendMode, HSVColor},
+        controller::{AnimationController, StartingPoint},
+        curve::Curve,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_two_controllers_on_one_strip() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut compositor = Compositor::<_, 6>::new(strip.clone());
+
+        let mut background = AnimationController::new(compositor.add_layer());
+        background
+            .queue_animation(
+                StaticAnimation::new(
+                    1,
+                    0..6,
+                    HSVColor::red(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+        let mut foreground = AnimationController::new(compositor.add_layer());
+        foreground
+            .queue_animation(
+                StaticAnimation::new(
+                    1,
+                    2..4,
+                    HSVColor::blue(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+
+        for _ in 0..2 {
+            foreground.update();
+            background.update();
+            compositor.compose();
+        }
+
+        let strip = strip.borrow();
+        let leds = (0..6)
+            .map(|led| strip.get_color_of_led(led))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            leds,
+            [
+                Color::red(),
+                Color::red(),
+                Color::blue(),
+                Color::blue(),
+                Color::red(),
+                Color::red()
+            ]
+        );
+    }
+}
@@ -3,8 +3,10 @@ use core::cell::RefCell;
 use alloc::{boxed::Box, rc::Rc, vec::Vec};
 
 use crate::{
-    animation::{Animation, TimedAnimation, TimedAnimationAt},
-    processing::{Processor, SingleAnimationProcessor, TimelineProcessor},
+    animation::{Animation, FadeToBlack, TimedAnimation, TimedAnimationAt},
+    color::Color,
+    indexing::LedId,
+    processing::{Processor, ProcessorKind, SingleAnimationProcessor, TimelineProcessor},
     strip::Strip,
     timeline::{Tick, Ticks, Timeline},
 };
@@ -15,7 +17,13 @@ pub enum StartingPoint {
     Now,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ControllerError {
+    /// The configured maximum of simultaneously running processors is reached.
+    MaxProcessorsReached(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct AnimationHandle(u32);
 
 impl AnimationHandle {
@@ -29,6 +37,14 @@ impl AnimationHandle {
     }
 }
 
+/// Describes a queued processor, e.g. for a debug overlay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessorInfo {
+    pub handle: AnimationHandle,
+    pub kind: ProcessorKind,
+    pub animation_count: usize,
+}
+
 struct Entry<P> {
     // start_time: Timestamp,
     processor: P,
@@ -38,8 +54,23 @@ struct Entry<P> {
 pub struct AnimationController<S> {
     processors: Vec<Entry<Box<dyn Processor>>>,
     current_tick: Tick,
+    rendered_tick: Tick,
+    speed: f32,
+    tick_fraction: f32,
+    tick_rate: Option<u32>,
+    clock: Option<(u64, Tick)>,
     // last_time: Timestamp,
     strip: Rc<RefCell<S>>,
+    motion_blur: f32,
+    previous_frame: Vec<Color>,
+    unblurred_frame: Vec<Color>,
+    frame_observer: Option<Box<dyn FnMut(&[Color])>>,
+    observed_frame: Vec<Color>,
+    pre_frame: Option<Box<dyn FnMut()>>,
+    post_frame: Option<Box<dyn FnMut()>>,
+    idle_fadeout: Option<Box<dyn Fn(Tick) -> Box<dyn Processor>>>,
+    idle_fadeout_handle: Option<AnimationHandle>,
+    max_processors: Option<usize>,
 }
 
 impl<S> AnimationController<S> {
@@ -47,12 +78,156 @@ impl<S> AnimationController<S> {
         Self {
             processors: Vec::new(),
             current_tick: 0,
+            rendered_tick: 0,
+            speed: 1.0,
+            tick_fraction: 0.0,
+            tick_rate: None,
+            clock: None,
             // last_time: 0,
             strip,
+            motion_blur: 0.0,
+            previous_frame: Vec::new(),
+            unblurred_frame: Vec::new(),
+            frame_observer: None,
+            observed_frame: Vec::new(),
+            pre_frame: None,
+            post_frame: None,
+            idle_fadeout: None,
+            idle_fadeout_handle: None,
+            max_processors: None,
+        }
+    }
+
+    /// Returns a handle to the controlled strip.
+    ///
+    /// Colors written directly to the strip may be overwritten by running animations on the next
+    /// [update](Self::update).
+    pub fn strip(&self) -> Rc<RefCell<S>> {
+        self.strip.clone()
+    }
+
+    /// Blends every new frame with the decayed previous frame, so moving lights leave a trail.
+    ///
+    /// `decay` is the share of the previous frame in the shown frame (0.0 disables motion blur),
+    /// every led is set to `new + (previous - new) * decay`. Animations still see the unblurred
+    /// frame on the strip.
+    pub fn set_motion_blur(&mut self, decay: f32) {
+        assert!(
+            (0.0..1.0).contains(&decay),
+            "motion blur decay must be in range 0..1"
+        );
+        self.motion_blur = decay;
+        self.previous_frame.clear();
+        self.unblurred_frame.clear();
+    }
+
+    /// Scales how many ticks every [update](Self::update) advances, e.g. `2.0` advances two ticks
+    /// and `0.5` advances one tick every other update.
+    ///
+    /// Fractional ticks are accumulated, so no ticks are lost. Animations whose last tick is
+    /// skipped still render it before they finish.
+    pub fn set_speed(&mut self, speed: f32) {
+        assert!(speed >= 0.0, "speed must not be negative");
+        self.speed = speed;
+        self.clock = None;
+    }
+
+    /// Sets how many ticks per second [update_at](Self::update_at) advances, the next call
+    /// establishes a new baseline at the current tick.
+    pub fn set_tick_rate(&mut self, ticks_per_second: u32) {
+        assert!(ticks_per_second > 0, "tick rate must be at least 1");
+        self.tick_rate = Some(ticks_per_second);
+        self.clock = None;
+    }
+
+    /// Returns the tick the next [update](Self::update) renders, see
+    /// [restore_tick](Self::restore_tick).
+    pub fn save_tick(&self) -> Tick {
+        self.current_tick
+    }
+
+    /// Jumps to a tick returned by [save_tick](Self::save_tick), the queued processors render
+    /// that tick again with the next [update](Self::update).
+    ///
+    /// Accumulated fractional ticks of [set_speed](Self::set_speed) are discarded. Animations
+    /// which start after the restored tick render nothing until their start is reached again.
+    pub fn restore_tick(&mut self, tick: Tick) {
+        self.current_tick = tick;
+        self.tick_fraction = 0.0;
+        self.clock = None;
+    }
+
+    /// Renders the tick before the last rendered tick again, e.g. for a frame by frame debugger.
+    /// At tick 0 the first tick is rendered again.
+    ///
+    /// The motion blur trail is cleared and the queued processors are
+    /// [reset](Processor::reset), so the animations start over from the colors they faded from.
+    /// Animations which have already finished and were removed can't be rendered again.
+    pub fn step_back(&mut self)
+    where
+        S: Strip,
+    {
+        self.restore_unblurred_frame();
+        // later processors painted over earlier ones, so their colors are restored first
+        for e in self.processors.iter_mut().rev() {
+            e.processor.reset();
         }
+        self.previous_frame.clear();
+        self.unblurred_frame.clear();
+
+        self.restore_tick(self.rendered_tick.saturating_sub(1));
+        self.update();
+    }
+
+    /// Calls `f` with the full frame after every [update](Self::update), e.g. to mirror the
+    /// output to a second destination.
+    pub fn set_frame_observer(&mut self, f: impl FnMut(&[Color]) + 'static) {
+        self.frame_observer = Some(Box::new(f));
+    }
+
+    /// Calls `f` at the start of every [update](Self::update).
+    pub fn set_pre_frame(&mut self, f: impl FnMut() + 'static) {
+        self.pre_frame = Some(Box::new(f));
+    }
+
+    /// Calls `f` at the end of every [update](Self::update).
+    pub fn set_post_frame(&mut self, f: impl FnMut() + 'static) {
+        self.post_frame = Some(Box::new(f));
     }
 
-    pub fn queue_timeline<T, A>(&mut self, timeline: T, _at_time: StartingPoint) -> AnimationHandle
+    /// Fades the whole strip to off over `ticks`, when the last queued animation finished.
+    ///
+    /// After the fadeout [has_no_work](Self::has_no_work) returns `true`, so the host can sleep.
+    pub fn set_idle_fadeout(&mut self, ticks: Ticks)
+    where
+        S: Strip + 'static,
+    {
+        let strip = self.strip.clone();
+        self.idle_fadeout = Some(Box::new(move |current_tick| {
+            let led_amount = LedId::try_from(S::LED_AMOUNT).unwrap();
+            Box::new(SingleAnimationProcessor::new(
+                TimedAnimation::new(current_tick, FadeToBlack::new(ticks, 0..led_amount)),
+                strip.clone(),
+            ))
+        }));
+    }
+
+    /// Limits how many animations and timelines can run at the same time, `None` removes the
+    /// limit.
+    ///
+    /// Already queued processors are kept, but further queueing fails until enough of them have
+    /// finished.
+    pub fn set_max_processors(&mut self, max_processors: Option<usize>) {
+        self.max_processors = max_processors;
+    }
+
+    /// Queues a timeline, fails if the [maximum of processors](Self::set_max_processors) is
+    /// reached.
+    pub fn queue_timeline<T, A>(
+        &mut self,
+        timeline: T,
+        _at_time: StartingPoint,
+    ) -> Result<AnimationHandle, ControllerError>
     where
         A: TimedAnimationAt<S> + 'static,
         T: Timeline<S, A> + 'static,
@@ -63,6 +238,7 @@ impl<S> AnimationController<S> {
         //     Timepoint::Relative(t) => self.last_time + t,
         //     Timepoint::Now => self.last_time + 1,
         // };
+        self.check_max_processors()?;
         let handle = AnimationHandle::new();
         self.processors.push(Entry {
             processor: Box::new(TimelineProcessor::new(timeline, self.strip.clone())),
@@ -70,14 +246,21 @@ impl<S> AnimationController<S> {
             handle: handle.clone(),
         });
 
-        handle
+        Ok(handle)
     }
 
-    pub fn queue_animation<A>(&mut self, animation: A, _at_time: StartingPoint) -> AnimationHandle
+    /// Queues a single animation, fails if the [maximum of processors](Self::set_max_processors)
+    /// is reached.
+    pub fn queue_animation<A>(
+        &mut self,
+        animation: A,
+        _at_time: StartingPoint,
+    ) -> Result<AnimationHandle, ControllerError>
     where
         A: Animation<S> + 'static,
         S: Strip + 'static,
     {
+        self.check_max_processors()?;
         let handle = AnimationHandle::new();
         self.processors.push(Entry {
             processor: Box::new(SingleAnimationProcessor::new(
@@ -87,26 +270,219 @@ impl<S> AnimationController<S> {
             handle: handle.clone(),
         });
 
-        handle
+        Ok(handle)
+    }
+
+    fn check_max_processors(&self) -> Result<(), ControllerError> {
+        match self.max_processors {
+            Some(max) if self.processors.len() >= max => {
+                Err(ControllerError::MaxProcessorsReached(max))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the name of the queued animation, if it was named with
+    /// [NamedAnimation](crate::animation::NamedAnimation).
+    pub fn name_of(&self, animation_handle: AnimationHandle) -> Option<&'static str> {
+        self.processors
+            .iter()
+            .find(|e| e.handle == animation_handle)
+            .and_then(|e| e.processor.name())
+    }
+
+    pub fn processor_infos(&self) -> Vec<ProcessorInfo> {
+        self.processors
+            .iter()
+            .map(|e| ProcessorInfo {
+                handle: e.handle,
+                kind: e.processor.kind(),
+                animation_count: e.processor.animation_count(),
+            })
+            .collect()
     }
 
     pub fn stop_animation(&mut self, animation_handle: AnimationHandle) {
         self.remove_processor(|e| e.handle == animation_handle);
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self)
+    where
+        S: Strip,
+    {
+        self.render(self.speed);
+    }
+
+    /// Renders the current tick and advances by `advance_by` ticks afterwards.
+    fn render(&mut self, advance_by: f32)
+    where
+        S: Strip,
+    {
+        if let Some(pre_frame) = self.pre_frame.as_mut() {
+            pre_frame();
+        }
+
+        let was_busy = !self.processors.is_empty();
+
+        if self.motion_blur > 0.0 {
+            self.restore_unblurred_frame();
+        }
+
         for e in self.processors.iter_mut() {
             e.processor.update(self.current_tick);
         }
 
+        if self.motion_blur > 0.0 {
+            self.apply_motion_blur();
+        }
+
+        if self.frame_observer.is_some() {
+            self.notify_frame_observer();
+        }
+
         self.remove_processor(|e| e.processor.has_no_work());
-        self.current_tick += 1;
+        let idle_fadeout_finished = self.forget_finished_idle_fadeout();
+        self.rendered_tick = self.current_tick;
+        self.advance_tick(advance_by);
+
+        // the fadeout itself finished, stay idle
+        if was_busy && self.processors.is_empty() && !idle_fadeout_finished {
+            self.on_idle();
+        }
+
+        if let Some(post_frame) = self.post_frame.as_mut() {
+            post_frame();
+        }
+    }
+
+    /// Like [update](Self::update), but advances by `elapsed` ticks (scaled by the
+    /// [speed](Self::set_speed)) after rendering the current tick, e.g. for a varying frame time.
+    pub fn update_with_elapsed(&mut self, elapsed: Ticks)
+    where
+        S: Strip,
+    {
+        self.render(elapsed as f32 * self.speed);
+    }
+
+    /// Renders the tick reached at the millisecond timestamp `now_ms` of a monotonic clock with
+    /// the [tick rate](Self::set_tick_rate) (scaled by the [speed](Self::set_speed)). Like
+    /// [update](Self::update) the following tick is the next one afterwards.
+    ///
+    /// The first call establishes the baseline and renders the current tick. Without a tick rate
+    /// every call advances one tick like [update](Self::update).
+    pub fn update_at(&mut self, now_ms: u64)
+    where
+        S: Strip,
+    {
+        let tick_rate = match self.tick_rate {
+            Some(tick_rate) => tick_rate,
+            None => return self.update(),
+        };
+        let (start_ms, start_tick) = *self.clock.get_or_insert((now_ms, self.current_tick));
+        let elapsed = now_ms.saturating_sub(start_ms) * u64::from(tick_rate) / 1000;
+        let elapsed = (elapsed as f32 * self.speed) as Ticks;
+
+        self.current_tick = start_tick.saturating_add(elapsed);
+        self.tick_fraction = 0.0;
+        self.render(1.0);
     }
 
     pub fn has_no_work(&self) -> bool {
         self.processors.len() == 0
     }
 
+    fn apply_motion_blur(&mut self)
+    where
+        S: Strip,
+    {
+        let mut strip = self.strip.borrow_mut();
+        self.unblurred_frame.clear();
+        self.unblurred_frame.extend(
+            (0..S::LED_AMOUNT)
+                .map(|led_id| strip.get_color_of_led(LedId::try_from(led_id).unwrap())),
+        );
+        // the first frame has no trail
+        if self.previous_frame.len() != S::LED_AMOUNT {
+            self.previous_frame = self.unblurred_frame.clone();
+        }
+
+        let frames = self
+            .previous_frame
+            .iter_mut()
+            .zip(self.unblurred_frame.iter());
+        for (led_id, (previous, current)) in frames.enumerate() {
+            let mut color = *current;
+            for channel in 0..3 {
+                let new = f32::from(current[channel]);
+                let old = f32::from(previous[channel]);
+                color[channel] = (new + (old - new) * self.motion_blur + 0.5) as u8;
+            }
+            strip.set_led_to_color(LedId::try_from(led_id).unwrap(), &color);
+            *previous = color;
+        }
+    }
+
+    /// Writes the last frame without motion blur back to the strip, so animations and leds which
+    /// aren't updated don't pick up the trail.
+    fn restore_unblurred_frame(&mut self)
+    where
+        S: Strip,
+    {
+        let mut strip = self.strip.borrow_mut();
+        for (led_id, color) in self.unblurred_frame.iter().enumerate() {
+            strip.set_led_to_color(LedId::try_from(led_id).unwrap(), color);
+        }
+    }
+
+    fn advance_tick(&mut self, ticks: f32) {
+        self.tick_fraction += ticks;
+        let ticks = self.tick_fraction as Ticks;
+        self.tick_fraction -= ticks as f32;
+        self.current_tick += ticks;
+    }
+
+    /// Forgets the handle of the idle fadeout once its processor was removed, returns whether
+    /// this happened with the last update.
+    fn forget_finished_idle_fadeout(&mut self) -> bool {
+        match self.idle_fadeout_handle {
+            Some(handle) if !self.processors.iter().any(|e| e.handle == handle) => {
+                self.idle_fadeout_handle = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn on_idle(&mut self) {
+        if let Some(idle_fadeout) = self.idle_fadeout.as_ref() {
+            let handle = AnimationHandle::new();
+            self.processors.push(Entry {
+                processor: idle_fadeout(self.current_tick),
+                handle,
+            });
+            self.idle_fadeout_handle = Some(handle);
+        }
+    }
+
+    fn notify_frame_observer(&mut self)
+    where
+        S: Strip,
+    {
+        {
+            let strip = self.strip.borrow();
+            self.observed_frame.clear();
+            self.observed_frame.extend(
+                (0..S::LED_AMOUNT)
+                    .map(|led_id| strip.get_color_of_led(LedId::try_from(led_id).unwrap())),
+            );
+        }
+
+        // the strip isn't borrowed anymore, so the observer may access it
+        if let Some(observer) = self.frame_observer.as_mut() {
+            observer(&self.observed_frame);
+        }
+    }
+
     fn remove_processor(&mut self, predicate: impl Fn(&Entry<Box<dyn Processor>>) -> bool) {
         self.processors
             .iter()
@@ -117,3 +493,606 @@ impl<S> AnimationController<S> {
             });
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::cell::RefCell;
+
+    use alloc::rc::Rc;
+
+    use crate::{
+        animation::{AnimationMeta, MeteorShower, MorphAnimation, NamedAnimation, StaticAnimation},
+        color::{BlendMode, HSVColor, LedColoring},
+        curve::Curve,
+        mock::SPI,
+        strip::mock::LedStrip,
+        timeline::DynTimelineBuilder,
+    };
+
+    use super::*;
+
+    /// Lights only the led at the position of the current tick, all other leds are off.
+    struct MovingDot;
+
+    impl<S: Strip> Animation<S> for MovingDot {
+        fn animate(
+            &self,
+            animation_tick: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            let dot = animation_tick as LedId;
+            Box::new((0..6).map(move |led| {
+                let v = if led == dot { 100 } else { 0 };
+                LedColoring::new(led, HSVColor::new(0, 0, v))
+            }))
+        }
+
+        fn duration(&self) -> Ticks {
+            5
+        }
+    }
+
+    #[test]
+    fn test_name_of_animation() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+
+        let named = controller
+            .queue_animation(
+                NamedAnimation::new(
+                    "wipe",
+                    StaticAnimation::new(
+                        10,
+                        0..6,
+                        HSVColor::red(),
+                        Curve::Linear,
+                        BlendMode::AllChannels,
+                    ),
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+        let unnamed = controller
+            .queue_animation(
+                StaticAnimation::new(
+                    10,
+                    0..6,
+                    HSVColor::blue(),
+                    Curve::Linear,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+
+        assert_eq!(controller.name_of(named), Some("wipe"));
+        assert_eq!(controller.name_of(unnamed), None);
+    }
+
+    #[test]
+    fn test_motion_blur_leaves_trail() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller.set_motion_blur(0.25);
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        for _ in 0..3 {
+            controller.update();
+        }
+
+        let strip = strip.borrow();
+        assert_eq!(strip.get_color_of_led(2), Color::init(191, 191, 191));
+        assert_eq!(strip.get_color_of_led(1), Color::init(48, 48, 48));
+        assert_eq!(strip.get_color_of_led(0), Color::init(16, 16, 16));
+        assert_eq!(strip.get_color_of_led(3), Color::off());
+    }
+
+    fn fade_frames(motion_blur: f32) -> Vec<Color> {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        for led in 0..6 {
+            strip.borrow_mut().set_led_to_color(led, &Color::white());
+        }
+        let mut controller = AnimationController::new(strip.clone());
+        controller.set_motion_blur(motion_blur);
+        controller
+            .queue_animation(FadeToBlack::new(4, 0..6), StartingPoint::Now)
+            .unwrap();
+
+        (0..8)
+            .map(|_| {
+                controller.update();
+                let color = strip.borrow().get_color_of_led(0);
+                color
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_motion_blur_trails_fade() {
+        let plain = fade_frames(0.0);
+        let blurred = fade_frames(0.5);
+
+        assert_eq!(blurred[0], plain[0]);
+        for tick in 1..=4 {
+            assert!(blurred[tick][0] > plain[tick][0], "tick: {}", tick);
+        }
+        assert_eq!(plain[4], Color::off());
+        assert!(blurred[5][0] > 0);
+        assert!(blurred[5][0] < blurred[4][0]);
+        assert!(blurred[7][0] < blurred[5][0]);
+    }
+
+    #[test]
+    fn test_no_trail_without_motion_blur() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        for _ in 0..3 {
+            controller.update();
+        }
+
+        let strip = strip.borrow();
+        assert_eq!(strip.get_color_of_led(2), Color::white());
+        assert_eq!(strip.get_color_of_led(1), Color::off());
+        assert_eq!(strip.get_color_of_led(0), Color::off());
+    }
+
+    #[test]
+    fn test_frame_observer_receives_frame() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+        let frames = Rc::new(RefCell::new(Vec::new()));
+
+        let observed = frames.clone();
+        controller.set_frame_observer(move |frame| observed.borrow_mut().push(frame.to_vec()));
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        controller.update();
+        controller.update();
+
+        let frames = frames.borrow();
+        assert_eq!(frames.len(), 2);
+        let mut expected = alloc::vec![Color::off(); 6];
+        expected[1] = Color::white();
+        assert_eq!(frames[1], expected);
+    }
+
+    #[test]
+    fn test_frame_observer_may_access_strip() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+
+        let observed_strip = strip.clone();
+        controller.set_frame_observer(move |frame| {
+            let mut strip = observed_strip.borrow_mut();
+            assert_eq!(strip.get_color_of_led(0), frame[0]);
+            strip.set_led_to_color(5, &Color::red());
+        });
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        controller.update();
+        assert_eq!(strip.borrow().get_color_of_led(5), Color::red());
+    }
+
+    #[test]
+    fn test_pre_and_post_frame_hooks() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let pre_calls = calls.clone();
+        controller.set_pre_frame(move || pre_calls.borrow_mut().push("pre"));
+        let post_calls = calls.clone();
+        controller.set_post_frame(move || post_calls.borrow_mut().push("post"));
+        let frame_calls = calls.clone();
+        controller.set_frame_observer(move |_| frame_calls.borrow_mut().push("frame"));
+
+        controller.update();
+        controller.update();
+
+        assert_eq!(
+            *calls.borrow(),
+            ["pre", "frame", "post", "pre", "frame", "post"]
+        );
+    }
+
+    #[test]
+    fn test_idle_fadeout() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+        let frames = Rc::new(RefCell::new(Vec::new()));
+
+        let observed = frames.clone();
+        controller.set_frame_observer(move |frame| observed.borrow_mut().push(frame[0]));
+        controller.set_idle_fadeout(4);
+        controller
+            .queue_animation(
+                StaticAnimation::new(
+                    1,
+                    0..6,
+                    HSVColor::red(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+
+        for _ in 0..20 {
+            if controller.has_no_work() {
+                break;
+            }
+            controller.update();
+        }
+        assert!(controller.has_no_work());
+
+        let frames = frames.borrow();
+        let lit = frames.iter().position(|c| *c == Color::red()).unwrap();
+        assert!(frames[lit..].iter().any(|c| c[0] > 0 && c[0] < 255));
+        assert_eq!(*frames.last().unwrap(), Color::off());
+
+        drop(frames);
+        controller.update();
+        controller.update();
+        assert!(controller.has_no_work());
+    }
+
+    #[test]
+    fn test_idle_fadeout_finishing_before_queued_animation() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+        controller.set_idle_fadeout(2);
+        controller
+            .queue_animation(
+                StaticAnimation::new(
+                    1,
+                    0..6,
+                    HSVColor::red(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+
+        for _ in 0..3 {
+            controller.update();
+        }
+        // only the fadeout is running
+        assert_eq!(controller.processor_infos().len(), 1);
+
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+        for _ in 0..4 {
+            controller.update();
+        }
+        // the fadeout finished while the dot is still running
+        assert_eq!(controller.processor_infos().len(), 1);
+
+        for _ in 0..3 {
+            controller.update();
+        }
+        // the dot finished and a new fadeout started
+        assert_eq!(controller.processor_infos().len(), 1);
+
+        for _ in 0..4 {
+            controller.update();
+        }
+        assert!(controller.has_no_work());
+    }
+
+    #[test]
+    fn test_strip_accessor() {
+        let mut controller =
+            AnimationController::new(Rc::new(RefCell::new(LedStrip::<SPI, 6>::new())));
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+        controller.update();
+        controller.update();
+
+        let strip = controller.strip();
+        assert_eq!(strip.borrow().get_color_of_led(1), Color::white());
+        assert_eq!(strip.borrow().get_color_of_led(0), Color::off());
+    }
+
+    #[test]
+    fn test_processor_infos() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+
+        let single = controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+        let timeline = DynTimelineBuilder::new()
+            .add_animation(0, MovingDot)
+            .add_animation(5, MovingDot)
+            .finish();
+        let timeline = controller
+            .queue_timeline(timeline, StartingPoint::Now)
+            .unwrap();
+
+        assert_eq!(
+            controller.processor_infos(),
+            [
+                ProcessorInfo {
+                    handle: single,
+                    kind: ProcessorKind::Single,
+                    animation_count: 1,
+                },
+                ProcessorInfo {
+                    handle: timeline,
+                    kind: ProcessorKind::Timeline,
+                    animation_count: 2,
+                },
+            ]
+        );
+    }
+
+    struct TickRecorder(Rc<RefCell<Vec<Tick>>>);
+
+    impl<S: Strip> Animation<S> for TickRecorder {
+        fn animate(
+            &self,
+            animation_tick: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            self.0.borrow_mut().push(animation_tick);
+            Box::new(core::iter::empty())
+        }
+
+        fn duration(&self) -> Ticks {
+            3
+        }
+    }
+
+    fn rendered_ticks(speed: f32) -> Vec<Tick> {
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let mut controller =
+            AnimationController::new(Rc::new(RefCell::new(LedStrip::<SPI, 6>::new())));
+        controller.set_speed(speed);
+        controller
+            .queue_animation(TickRecorder(ticks.clone()), StartingPoint::Now)
+            .unwrap();
+        while !controller.has_no_work() {
+            controller.update();
+        }
+        let ticks = ticks.borrow().clone();
+        ticks
+    }
+
+    #[test]
+    fn test_speed() {
+        assert_eq!(rendered_ticks(1.0), [0, 1, 2, 3]);
+        assert_eq!(rendered_ticks(0.5), [0, 0, 1, 1, 2, 2, 3, 3]);
+        assert_eq!(rendered_ticks(2.0), [0, 2, 3]);
+        assert_eq!(rendered_ticks(1.5), [0, 1, 3]);
+    }
+
+    #[test]
+    fn test_save_and_restore_tick() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 30>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(
+                MeteorShower::new(100, 0..30, HSVColor::new(200, 100, 100), 4, 3, 0.3, 99),
+                StartingPoint::Now,
+            )
+            .unwrap();
+        let frame = || {
+            (0..30)
+                .map(|led| strip.borrow().get_color_of_led(led))
+                .collect::<Vec<_>>()
+        };
+
+        for _ in 0..20 {
+            controller.update();
+        }
+        let saved = controller.save_tick();
+        assert_eq!(saved, 20);
+        controller.update();
+        let expected = frame();
+
+        for _ in 0..10 {
+            controller.update();
+        }
+
+        controller.restore_tick(saved);
+        controller.update();
+        assert_eq!(frame(), expected);
+    }
+
+    #[test]
+    fn test_restore_tick_before_start() {
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let mut controller =
+            AnimationController::new(Rc::new(RefCell::new(LedStrip::<SPI, 6>::new())));
+        controller
+            .queue_timeline(
+                DynTimelineBuilder::new()
+                    .add_animation(0, TickRecorder(ticks.clone()))
+                    .repeating()
+                    .finish(),
+                StartingPoint::Now,
+            )
+            .unwrap();
+        for _ in 0..5 {
+            controller.update();
+        }
+        controller
+            .queue_animation(TickRecorder(ticks.clone()), StartingPoint::Now)
+            .unwrap();
+        assert_eq!(*ticks.borrow(), [1, 2, 3]);
+
+        // neither the queued animation nor the second iteration of the timeline has started
+        controller.restore_tick(2);
+        controller.update();
+        assert_eq!(*ticks.borrow(), [1, 2, 3]);
+
+        controller.restore_tick(5);
+        controller.update();
+        assert_eq!(*ticks.borrow(), [1, 2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_step_back() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+        let frame = || {
+            (0..6)
+                .map(|led| strip.borrow().get_color_of_led(led))
+                .collect::<Vec<_>>()
+        };
+
+        let mut frames = Vec::new();
+        for _ in 0..3 {
+            controller.update();
+            frames.push(frame());
+        }
+
+        controller.step_back();
+        assert_eq!(frame(), frames[1]);
+        controller.step_back();
+        assert_eq!(frame(), frames[0]);
+        controller.step_back();
+        assert_eq!(frame(), frames[0]);
+
+        controller.update();
+        assert_eq!(frame(), frames[1]);
+    }
+
+    #[test]
+    fn test_step_back_resets_animations() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        for led in 0..6 {
+            strip.borrow_mut().set_led_to_color(led, &Color::white());
+        }
+        let mut controller = AnimationController::new(strip.clone());
+        controller.update();
+        controller
+            .queue_animation(
+                MorphAnimation::new(
+                    4,
+                    0..6,
+                    HSVColor::red(),
+                    HSVColor::blue(),
+                    BlendMode::AllChannels,
+                ),
+                StartingPoint::Now,
+            )
+            .unwrap();
+        controller.update();
+        controller.update();
+
+        controller.step_back();
+        assert_eq!(strip.borrow().get_color_of_led(0), Color::red());
+        // the morph starts at tick 1, so the colors it morphed from are shown again
+        controller.step_back();
+        assert_eq!(strip.borrow().get_color_of_led(0), Color::white());
+    }
+
+    #[test]
+    fn test_update_at() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller.set_tick_rate(100);
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+        let lit_led = || {
+            (0..6)
+                .position(|led| strip.borrow().get_color_of_led(led) == Color::white())
+                .unwrap()
+        };
+
+        controller.update_at(1000);
+        assert_eq!(lit_led(), 0);
+        assert_eq!(controller.save_tick(), 1);
+
+        controller.update_at(1020);
+        assert_eq!(lit_led(), 2);
+        assert_eq!(controller.save_tick(), 3);
+
+        controller.update_at(1025);
+        assert_eq!(lit_led(), 2);
+
+        controller.update_at(1039);
+        assert_eq!(lit_led(), 3);
+
+        controller.update_at(1040);
+        assert_eq!(lit_led(), 4);
+        assert_eq!(controller.save_tick(), 5);
+    }
+
+    #[test]
+    fn test_update_at_without_tick_rate() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        controller.update_at(1000);
+        controller.update_at(5000);
+        assert_eq!(strip.borrow().get_color_of_led(1), Color::white());
+        assert_eq!(controller.save_tick(), 2);
+    }
+
+    #[test]
+    fn test_update_with_elapsed_renders_before_advancing() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip.clone());
+        controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .unwrap();
+
+        controller.update_with_elapsed(3);
+        assert_eq!(strip.borrow().get_color_of_led(0), Color::white());
+        controller.update_with_elapsed(1);
+        assert_eq!(strip.borrow().get_color_of_led(3), Color::white());
+        assert_eq!(controller.save_tick(), 4);
+    }
+
+    #[test]
+    fn test_max_processors() {
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 6>::new()));
+        let mut controller = AnimationController::new(strip);
+        controller.set_max_processors(Some(2));
+
+        assert!(controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .is_ok());
+        assert!(controller
+            .queue_timeline(
+                DynTimelineBuilder::new()
+                    .add_animation(0, MovingDot)
+                    .finish(),
+                StartingPoint::Now
+            )
+            .is_ok());
+        assert_eq!(
+            controller.queue_animation(MovingDot, StartingPoint::Now),
+            Err(ControllerError::MaxProcessorsReached(2))
+        );
+
+        controller.set_max_processors(None);
+        assert!(controller
+            .queue_animation(MovingDot, StartingPoint::Now)
+            .is_ok());
+    }
+}
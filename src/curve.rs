@@ -1,14 +1,46 @@
+use alloc::{boxed::Box, rc::Rc, vec::Vec};
+use core::fmt;
 use keyframe::{ease_with_scaled_time, functions, CanTween};
 
-use super::timeline::{Tick, Ticks};
+use super::{
+    timeline::{Tick, Ticks},
+    util::clamp_percentage,
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Curve {
     Linear,
     Step,
     EaseIn,
     EaseOut,
     EaseInOut,
+    /// Splits the duration into segments `(fraction, curve)`, every segment covers its fraction
+    /// of the time and of the transition and is eased with its own curve. The fractions are
+    /// normalized to their sum.
+    Segmented(Vec<(f32, Curve)>),
+    /// Runs the inner curve forward in the first half of the duration and backward in the
+    /// second half, so the transition reaches `to` in the middle and ends at `from` again.
+    YoYo(Box<Curve>),
+    /// User supplied easing function, which maps the normalized time (0.0 - 1.0) to the
+    /// progress of the transition. Can't be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Rc<dyn Fn(f32) -> f32>),
+}
+
+impl fmt::Debug for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Curve::Linear => write!(f, "Linear"),
+            Curve::Step => write!(f, "Step"),
+            Curve::EaseIn => write!(f, "EaseIn"),
+            Curve::EaseOut => write!(f, "EaseOut"),
+            Curve::EaseInOut => write!(f, "EaseInOut"),
+            Curve::Segmented(segments) => f.debug_tuple("Segmented").field(segments).finish(),
+            Curve::YoYo(curve) => f.debug_tuple("YoYo").field(curve).finish(),
+            Curve::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+        }
+    }
 }
 
 pub(crate) fn calculate_with_curve<H: CanTween + Copy>(
@@ -59,7 +91,65 @@ pub(crate) fn calculate_with_curve<H: CanTween + Copy>(
             current_tick as f32,
             duration as f32,
         ),
+        Curve::Segmented(segments) => {
+            let time = normalized_time(duration, current_tick);
+            H::ease(from, to, f64::from(segmented_progress(segments, time)))
+        }
+        Curve::YoYo(curve) => {
+            if duration == 0 {
+                return from;
+            }
+            calculate_with_curve(
+                curve,
+                duration,
+                &from,
+                &to,
+                yoyo_tick(duration, current_tick),
+            )
+        }
+        Curve::Custom(ease) => {
+            let time = normalized_time(duration, current_tick);
+            H::ease(from, to, f64::from(ease(time)))
+        }
+    }
+}
+
+/// Mirrors the ticks of the second half of the duration back onto the full duration.
+fn yoyo_tick(duration: Ticks, current_tick: Tick) -> Tick {
+    let forward = u64::from(current_tick.min(duration)) * 2;
+    let duration = u64::from(duration);
+    let mirrored = if forward <= duration {
+        forward
+    } else {
+        2 * duration - forward
+    };
+    Tick::try_from(mirrored).unwrap()
+}
+
+fn normalized_time(duration: Ticks, current_tick: Tick) -> f32 {
+    if duration == 0 {
+        1.0
+    } else {
+        (current_tick as f32 / duration as f32).min(1.0)
+    }
+}
+
+fn segmented_progress(segments: &[(f32, Curve)], time: f32) -> f32 {
+    let total: f32 = segments.iter().map(|s| s.0.max(0.0)).sum();
+    if total <= 0.0 {
+        return time;
     }
+
+    let mut start = 0.0;
+    for (index, (fraction, curve)) in segments.iter().enumerate() {
+        let len = fraction.max(0.0) / total;
+        if time < start + len || index == segments.len() - 1 {
+            let local = if len > 0.0 { (time - start) / len } else { 1.0 };
+            return start + len * calculate_with_curve_percentage(curve, &0f32, &1f32, local);
+        }
+        start += len;
+    }
+    time
 }
 
 pub(crate) fn calculate_with_curve_percentage<H: CanTween + Copy>(
@@ -68,5 +158,146 @@ pub(crate) fn calculate_with_curve_percentage<H: CanTween + Copy>(
     to: &H,
     percentage: f32,
 ) -> H {
+    let percentage = clamp_percentage(percentage);
     calculate_with_curve(curve, 1000, from, to, (percentage * 1000f32) as Tick)
 }
+
+/// Precomputed values of a curve for every tick of a duration.
+///
+/// Avoids evaluating the easing function for every led, when many leds share the same curve
+/// and duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EaseTable {
+    values: Vec<f32>,
+}
+
+impl EaseTable {
+    pub fn new(curve: &Curve, duration: Ticks) -> Self {
+        let values = (0..=duration)
+            .map(|tick| calculate_with_curve(curve, duration, &0f32, &1f32, tick))
+            .collect();
+        Self { values }
+    }
+
+    /// Same as [calculate_with_curve] with the curve and duration of the table. Ticks after the
+    /// duration are clamped to the end.
+    pub fn calculate<H: CanTween + Copy>(&self, from: &H, to: &H, current_tick: Tick) -> H {
+        let index = (current_tick as usize).min(self.values.len() - 1);
+        H::ease(*from, *to, f64::from(self.values[index]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::color::HSVColor;
+
+    use super::*;
+
+    #[test]
+    fn test_ease_table_matches_direct_calculation() {
+        let from = HSVColor::new(0, 0, 0);
+        let to = HSVColor::new(100, 100, 100);
+        let duration = 37;
+        let table = EaseTable::new(&Curve::Linear, duration);
+
+        for tick in 0..=duration {
+            assert_eq!(
+                table.calculate(&from, &to, tick),
+                calculate_with_curve(&Curve::Linear, duration, &from, &to, tick),
+                "tick: {}",
+                tick
+            );
+        }
+        assert_eq!(table.calculate(&from, &to, duration + 5), to);
+    }
+
+    #[test]
+    fn test_segmented_curve() {
+        let curve = Curve::Segmented(alloc::vec![(0.5, Curve::Linear), (0.5, Curve::EaseOut)]);
+        let at = |tick| calculate_with_curve(&curve, 100, &0f32, &1f32, tick);
+
+        assert_eq!(at(0), 0.0);
+        assert_eq!(at(25), 0.25);
+        assert_eq!(at(50), 0.5);
+        assert_eq!(at(100), 1.0);
+
+        let ease_out = calculate_with_curve(&Curve::EaseOut, 100, &0f32, &1f32, 50);
+        assert!((at(75) - (0.5 + 0.5 * ease_out)).abs() < 1e-3);
+        assert!(at(75) > 0.75);
+
+        let weighted = Curve::Segmented(alloc::vec![(1.0, Curve::Step), (3.0, Curve::Linear)]);
+        assert_eq!(calculate_with_curve(&weighted, 100, &0f32, &1f32, 10), 0.0);
+        assert_eq!(calculate_with_curve(&weighted, 100, &0f32, &1f32, 25), 0.25);
+        assert_eq!(calculate_with_curve(&weighted, 100, &0f32, &1f32, 100), 1.0);
+    }
+
+    #[test]
+    fn test_yoyo_curve() {
+        let curve = Curve::YoYo(Box::new(Curve::Linear));
+        let at = |tick| calculate_with_curve(&curve, 10, &0f32, &1f32, tick);
+
+        assert_eq!(at(0), 0.0);
+        assert_eq!(at(5), 1.0);
+        assert_eq!(at(10), 0.0);
+        assert_eq!(at(15), 0.0);
+        for tick in 0..=5 {
+            assert_eq!(at(tick), at(10 - tick), "tick: {}", tick);
+            assert!(tick == 0 || at(tick) > at(tick - 1));
+        }
+
+        let from = HSVColor::new(0, 100, 20);
+        let to = HSVColor::new(0, 100, 80);
+        let color_at = |tick| calculate_with_curve(&curve, 10, &from, &to, tick);
+        assert_eq!(color_at(0), from);
+        assert_eq!(color_at(5), to);
+        assert_eq!(color_at(10), from);
+        assert_eq!(color_at(3), color_at(7));
+    }
+
+    #[test]
+    fn test_custom_curve() {
+        let curve = Curve::Custom(Rc::new(|t: f32| t * t));
+        let cloned = curve.clone();
+        let at = |tick| calculate_with_curve(&cloned, 10, &0f32, &1f32, tick);
+
+        assert_eq!(at(0), 0.0);
+        assert_eq!(at(5), 0.25);
+        assert_eq!(at(10), 1.0);
+        assert_eq!(at(20), 1.0);
+        assert_eq!(
+            calculate_with_curve_percentage(&curve, &0f32, &100f32, 0.5),
+            25.0
+        );
+        assert_eq!(calculate_with_curve(&curve, 0, &0f32, &1f32, 0), 1.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let curve = Curve::Segmented(alloc::vec![(0.5, Curve::EaseIn), (0.5, Curve::Step)]);
+        let json = serde_json::to_string(&curve).unwrap();
+        let decoded = serde_json::from_str::<Curve>(&json).unwrap();
+
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        for tick in 0..=10 {
+            assert_eq!(
+                calculate_with_curve(&decoded, 10, &0f32, &1f32, tick),
+                calculate_with_curve(&curve, 10, &0f32, &1f32, tick)
+            );
+        }
+    }
+
+    #[test]
+    fn test_ease_table_values() {
+        let table = EaseTable::new(&Curve::EaseInOut, 10);
+
+        assert_eq!(table.calculate(&0f32, &1f32, 0), 0.0);
+        assert_eq!(table.calculate(&0f32, &1f32, 10), 1.0);
+        for tick in 0..10 {
+            let value = table.calculate(&0f32, &1f32, tick);
+            let direct = calculate_with_curve(&Curve::EaseInOut, 10, &0f32, &1f32, tick);
+            assert!((value - direct).abs() < 1e-6, "tick: {}", tick);
+            assert!(value <= table.calculate(&0f32, &1f32, tick + 1));
+        }
+    }
+}
@@ -64,6 +64,20 @@ pub trait IndexingExt: Indexing {
     {
         CircularIndexed::new(self, offset)
     }
+
+    fn zigzag(self, stripe_len: usize) -> ZigzagIndexed<Self>
+    where
+        Self: Sized,
+    {
+        ZigzagIndexed::new(self, stripe_len)
+    }
+
+    fn clamped(self) -> ClampedIndexed<Self>
+    where
+        Self: Sized,
+    {
+        ClampedIndexed::new(self)
+    }
 }
 
 impl<M: Indexing> IndexingExt for M {}
@@ -81,8 +95,11 @@ impl<I: Indexing> Indexing for ReversedIndexed<I> {
     type OutputIndex = <I as Indexing>::OutputIndex;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
-        self.0
-            .index(Index::try_from(self.0.len()).unwrap() - index - 1)
+        let len = Index::try_from(self.0.len()).map_err(|_| MappingError::IndexOutOfBounds)?;
+        if index >= len {
+            return Err(MappingError::IndexOutOfBounds);
+        }
+        self.0.index(len - index - 1)
     }
 
     fn len(&self) -> usize {
@@ -146,7 +163,7 @@ impl<I: Indexing> Indexing for CircularIndexed<I> {
     type OutputIndex = <I as Indexing>::OutputIndex;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
-        let len = Index::try_from(self.0.len()).unwrap();
+        let len = Index::try_from(self.0.len()).map_err(|_| MappingError::IndexOutOfBounds)?;
         if index >= len {
             return Err(MappingError::IndexOutOfBounds);
         }
@@ -166,6 +183,151 @@ impl<I: Indexing> Indexing for CircularIndexed<I> {
     }
 }
 
+/// Will reverse the order within every odd stripe of `stripe_len` leds.
+///
+/// This is useful for zigzag installations, where every other run of leds is mounted reversed.
+///
+/// # Example
+/// ```
+/// # use led_strip_animations::indexing::{ZigzagIndexed, Indexing};
+/// let indexes = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+/// let zigzag = ZigzagIndexed::new(&indexes, 3);
+///
+/// assert_eq!(*zigzag.index(2).unwrap(), 2);
+/// assert_eq!(*zigzag.index(3).unwrap(), 5);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ZigzagIndexed<I>(I, usize);
+
+impl<I> ZigzagIndexed<I> {
+    /// stripe_len != 0
+    pub fn new(indexer: I, stripe_len: usize) -> Self {
+        assert!(stripe_len > 0, "stripe length must be at least 1");
+        Self(indexer, stripe_len)
+    }
+}
+
+impl<I: Indexing> Indexing for ZigzagIndexed<I> {
+    type OutputIndex = <I as Indexing>::OutputIndex;
+
+    fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
+        let len = self.0.len();
+        let index = usize::from(index);
+        if index >= len {
+            return Err(MappingError::IndexOutOfBounds);
+        }
+
+        let stripe = index / self.1;
+        let stripe_start = stripe * self.1;
+        let index = if stripe % 2 == 0 {
+            index
+        } else {
+            let stripe_len = self.1.min(len - stripe_start);
+            stripe_start + stripe_len - 1 - (index - stripe_start)
+        };
+
+        self.0
+            .index(Index::try_from(index).map_err(|_| MappingError::IndexOutOfBounds)?)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Will clamp out of range indexes to the last index instead of returning an error.
+///
+/// Only an empty indexer still returns an error.
+///
+/// # Example
+/// ```
+/// # use led_strip_animations::indexing::{ClampedIndexed, Indexing};
+/// let indexes = [0, 1, 2, 3, 4];
+/// let clamped = ClampedIndexed::new(&indexes);
+///
+/// assert_eq!(*clamped.index(2).unwrap(), 2);
+/// assert_eq!(*clamped.index(7).unwrap(), 4);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ClampedIndexed<I>(I);
+
+impl<I> ClampedIndexed<I> {
+    pub fn new(indexer: I) -> Self {
+        Self(indexer)
+    }
+}
+
+impl<I: Indexing> Indexing for ClampedIndexed<I> {
+    type OutputIndex = <I as Indexing>::OutputIndex;
+
+    fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
+        let len = self.0.len();
+        if len == 0 {
+            return Err(MappingError::IndexOutOfBounds);
+        }
+        let last = Index::try_from(len - 1).unwrap_or(Index::MAX);
+        self.0.index(index.min(last))
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// Row major layout of a led matrix with `width` columns and `height` rows.
+///
+/// # Example
+/// ```
+/// # use led_strip_animations::indexing::Matrix;
+/// let matrix = Matrix::new(4, 3);
+///
+/// assert_eq!(matrix.led_at(1, 2), 9);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Matrix {
+    width: u16,
+    height: u16,
+}
+
+impl Matrix {
+    pub fn new(width: u16, height: u16) -> Self {
+        assert!(
+            u32::from(width) * u32::from(height) <= u32::from(Index::MAX) + 1,
+            "matrix has more leds than can be addressed"
+        );
+        Self { width, height }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// x < width, y < height
+    pub fn led_at(&self, x: u16, y: u16) -> LedId {
+        assert!(x < self.width && y < self.height, "position out of matrix");
+        y * self.width + x
+    }
+}
+
+impl Indexing for Matrix {
+    type OutputIndex = SingleIndexed;
+
+    fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
+        if usize::from(index) >= self.len() {
+            return Err(MappingError::IndexOutOfBounds);
+        }
+        Ok(SingleIndexed::new(index))
+    }
+
+    fn len(&self) -> usize {
+        usize::from(self.width) * usize::from(self.height)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum Bound {
     Relative(usize),
@@ -201,11 +363,23 @@ impl<I: Indexing> BoundedIndexed<I> {
     /// assert_eq!(*bounded.index(4).unwrap(), 6);
     /// ```
     pub fn from_range<R: RangeBounds<LedId>>(indexer: I, range: R) -> Self {
-        Self(
-            indexer,
-            core_bounds_to_bounds(range.start_bound(), true),
-            core_bounds_to_bounds(range.end_bound(), false),
-        )
+        // normalized to an inclusive start and an exclusive end
+        let front_bound = match range.start_bound() {
+            core::ops::Bound::Included(start) => Bound::Absolute(usize::from(*start)),
+            core::ops::Bound::Excluded(start) => Bound::Absolute(usize::from(*start) + 1),
+            core::ops::Bound::Unbounded => Bound::None,
+        };
+        let end = match range.end_bound() {
+            core::ops::Bound::Included(end) => Some(usize::from(*end) + 1),
+            core::ops::Bound::Excluded(end) => Some(usize::from(*end)),
+            core::ops::Bound::Unbounded => None,
+        };
+        let end_bound = match end {
+            Some(end) => Bound::Relative(indexer.len().saturating_sub(end)),
+            None => Bound::None,
+        };
+
+        Self(indexer, front_bound, end_bound)
     }
 
     /// Creates a new bounded index mapping.
@@ -224,6 +398,26 @@ impl<I: Indexing> BoundedIndexed<I> {
         Self(indexer, front_bound, end_bound)
     }
 
+    /// Creates a new bounded index mapping from percentages (0.0 - 100.0) of the inner length.
+    ///
+    /// The bounds are rounded to the nearest led. If `start_pct >= end_pct` the mapping is empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use led_strip_animations::indexing::{BoundedIndexed, Indexing};
+    /// let bounded = BoundedIndexed::from_percent(0..100, 25.0, 75.0);
+    ///
+    /// assert_eq!(bounded.len(), 50);
+    /// assert_eq!(*bounded.index(0).unwrap(), 25);
+    /// ```
+    pub fn from_percent(indexer: I, start_pct: f32, end_pct: f32) -> Self {
+        let len = indexer.len();
+        let to_led = |pct: f32| (len as f32 * pct.clamp(0.0, 100.0) / 100.0 + 0.5) as usize;
+        let start = to_led(start_pct).min(len);
+        let end = to_led(end_pct).min(len).max(start);
+        Self(indexer, Bound::Absolute(start), Bound::Relative(len - end))
+    }
+
     fn front_off(&self) -> usize {
         match self.1 {
             Bound::None => 0,
@@ -235,20 +429,11 @@ impl<I: Indexing> BoundedIndexed<I> {
         match self.2 {
             Bound::None => 0,
             Bound::Relative(o) => o,
-            Bound::Absolute(o) => self.0.len() - o - 1,
+            Bound::Absolute(o) => self.0.len().saturating_sub(o + 1),
         }
     }
 }
 
-fn core_bounds_to_bounds(core_bound: core::ops::Bound<&LedId>, start: bool) -> Bound {
-    let off = if start { 0 } else { 1 };
-    match core_bound {
-        core::ops::Bound::Included(o) => Bound::Absolute((*o + off) as usize),
-        core::ops::Bound::Excluded(o) => Bound::Absolute((*o - off) as usize),
-        core::ops::Bound::Unbounded => Bound::None,
-    }
-}
-
 impl<I: Indexing> Indexing for BoundedIndexed<I> {
     type OutputIndex = <I as Indexing>::OutputIndex;
 
@@ -263,7 +448,7 @@ impl<I: Indexing> Indexing for BoundedIndexed<I> {
     fn len(&self) -> usize {
         let front_off = self.front_off();
         let end_off = self.end_off();
-        self.0.len() - front_off - end_off
+        self.0.len().saturating_sub(front_off + end_off)
     }
 }
 
@@ -325,6 +510,10 @@ impl<I: Indexing> Indexing for HalfIndexed<I> {
     type OutputIndex = <I as Indexing>::OutputIndex;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
+        if usize::from(index) >= self.len() {
+            return Err(MappingError::NotInMappingRange);
+        }
+
         if self.1 {
             self.0.index(index)
         } else {
@@ -461,7 +650,9 @@ impl Indexing for &[LedId] {
     type OutputIndex = SingleIndexed;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
-        Ok(SingleIndexed::new(self[usize::from(index)]))
+        self.get(usize::from(index))
+            .map(|index| SingleIndexed::new(*index))
+            .ok_or(MappingError::IndexOutOfBounds)
     }
 
     fn len(&self) -> usize {
@@ -473,7 +664,9 @@ impl<const N: usize> Indexing for &[LedId; N] {
     type OutputIndex = SingleIndexed;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
-        Ok(SingleIndexed::new(self[usize::from(index)]))
+        self.get(usize::from(index))
+            .map(|index| SingleIndexed::new(*index))
+            .ok_or(MappingError::IndexOutOfBounds)
     }
 
     fn len(&self) -> usize {
@@ -485,7 +678,9 @@ impl<const N: usize> Indexing for [LedId; N] {
     type OutputIndex = SingleIndexed;
 
     fn index(&self, index: Index) -> Result<Self::OutputIndex, MappingError> {
-        Ok(SingleIndexed::new(self[usize::from(index)]))
+        self.get(usize::from(index))
+            .map(|index| SingleIndexed::new(*index))
+            .ok_or(MappingError::IndexOutOfBounds)
     }
 
     fn len(&self) -> usize {
@@ -532,6 +727,60 @@ mod test {
         assert_matches!(bounded.index(5), Err(MappingError::NotInMappingRange));
     }
 
+    #[test]
+    fn test_bounded_indexed_from_range_bounds() {
+        let indexed = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+        let bounded = BoundedIndexed::from_range(&indexed, 2..=6);
+        assert_eq!(bounded.len(), 5);
+        assert_eq!(*bounded.index(0).unwrap(), 2);
+        assert_eq!(*bounded.index(4).unwrap(), 6);
+
+        let bounded = BoundedIndexed::from_range(
+            &indexed,
+            (core::ops::Bound::Excluded(1), core::ops::Bound::Excluded(4)),
+        );
+        assert_eq!(bounded.len(), 2);
+        assert_eq!(*bounded.index(0).unwrap(), 2);
+        assert_eq!(*bounded.index(1).unwrap(), 3);
+
+        let bounded = BoundedIndexed::from_range(&indexed, ..3);
+        assert_eq!(bounded.len(), 3);
+        assert_eq!(*bounded.index(2).unwrap(), 2);
+
+        let bounded = BoundedIndexed::from_range(&indexed, 5..);
+        assert_eq!(bounded.len(), 4);
+        assert_eq!(*bounded.index(3).unwrap(), 8);
+
+        let bounded = BoundedIndexed::from_range(&indexed, 3..20);
+        assert_eq!(bounded.len(), 6);
+
+        assert_eq!(BoundedIndexed::from_range(&indexed, 0..0).len(), 0);
+        assert_eq!(BoundedIndexed::from_range(&indexed, 0..=0).len(), 1);
+        assert_eq!(BoundedIndexed::from_range(&indexed, 6..2).len(), 0);
+    }
+
+    #[test]
+    fn test_bounded_indexed_from_percent() {
+        let bounded = BoundedIndexed::from_percent(0u16..100, 25.0, 75.0);
+
+        assert_eq!(bounded.len(), 50);
+        assert_eq!(*bounded.index(0).unwrap(), 25);
+        assert_eq!(*bounded.index(49).unwrap(), 74);
+        assert_matches!(bounded.index(50), Err(MappingError::NotInMappingRange));
+
+        let bounded = BoundedIndexed::from_percent(0u16..10, 12.0, 56.0);
+        assert_eq!(bounded.len(), 5);
+        assert_eq!(*bounded.index(0).unwrap(), 1);
+
+        let bounded = BoundedIndexed::from_percent(0u16..100, 75.0, 25.0);
+        assert_eq!(bounded.len(), 0);
+        assert_matches!(bounded.index(0), Err(MappingError::NotInMappingRange));
+
+        let bounded = BoundedIndexed::from_percent(0u16..100, -10.0, 150.0);
+        assert_eq!(bounded.len(), 100);
+    }
+
     #[test]
     fn test_split_mirrored_indexed() {
         let indexed = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
@@ -613,6 +862,77 @@ mod test {
         assert_eq!(*h2_reversed.index(4).unwrap(), 5);
     }
 
+    #[test]
+    fn test_zigzag_indexed() {
+        let zigzag = (0u16..9).zigzag(3);
+
+        assert_eq!(zigzag.len(), 9);
+        let mapped = (0..9)
+            .map(|i| *zigzag.index(i).unwrap())
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(mapped, [0, 1, 2, 5, 4, 3, 6, 7, 8]);
+        assert_matches!(zigzag.index(9), Err(MappingError::IndexOutOfBounds));
+
+        let zigzag = (0u16..8).zigzag(3);
+        assert_eq!(*zigzag.index(6).unwrap(), 6);
+        assert_eq!(*zigzag.index(7).unwrap(), 7);
+
+        let zigzag = (0u16..5).zigzag(3);
+        assert_eq!(*zigzag.index(3).unwrap(), 4);
+        assert_eq!(*zigzag.index(4).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_empty_indexing() {
+        let empty: &[LedId] = &[];
+        assert_eq!(empty.len(), 0);
+        assert_matches!(empty.index(0), Err(MappingError::IndexOutOfBounds));
+
+        let range = 3u16..3;
+        assert_eq!(range.clone().reversed().len(), 0);
+        assert_matches!(
+            range.clone().reversed().index(0),
+            Err(MappingError::IndexOutOfBounds)
+        );
+        assert_eq!(range.clone().every_nth(2).len(), 0);
+        assert_eq!(range.clone().zigzag(3).len(), 0);
+
+        let (lower, upper) = range.clone().split_into_half(UnevenBehavior::ToUpper);
+        assert_eq!(lower.len(), 0);
+        assert_eq!(upper.len(), 0);
+        assert_matches!(upper.index(0), Err(MappingError::NotInMappingRange));
+
+        let mirrored = range.clone().split_mirrored(UnevenBehavior::ToLower);
+        assert_eq!(mirrored.len(), 0);
+        assert_matches!(mirrored.index(0), Err(MappingError::NotInMappingRange));
+
+        let bounded = (0u16..10).bounded(0..0);
+        assert_eq!(bounded.len(), 0);
+        assert_matches!(bounded.index(0), Err(MappingError::NotInMappingRange));
+
+        let bounded = BoundedIndexed::from_bounds(range, Bound::Absolute(2), Bound::Absolute(4));
+        assert_eq!(bounded.len(), 0);
+    }
+
+    #[test]
+    fn test_clamped_indexed() {
+        let clamped = (0u16..5).clamped();
+
+        assert_eq!(clamped.len(), 5);
+        assert_eq!(*clamped.index(0).unwrap(), 0);
+        assert_eq!(*clamped.index(3).unwrap(), 3);
+        assert_eq!(*clamped.index(5).unwrap(), 4);
+        assert_eq!(*clamped.index(Index::MAX).unwrap(), 4);
+
+        let reversed = (0u16..5).reversed().clamped();
+        assert_eq!(*reversed.index(9).unwrap(), 0);
+
+        assert_matches!(
+            (3u16..3).clamped().index(0),
+            Err(MappingError::IndexOutOfBounds)
+        );
+    }
+
     #[test]
     fn test_circular_indexed() {
         let indexes = [0, 1, 2, 3, 4, 5, 6, 7, 8];
@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+
+use crate::{
+    color::{HSVColor, Spectrum, TransparentColor},
+    curve::{calculate_with_curve_percentage, Curve},
+    util::clamp_percentage,
+};
+
+/// Spectrum defined by keyframes `(percentage, color)`, which is interpolated between the
+/// keyframes.
+///
+/// Every segment between two keyframes is eased with its own [Curve] (default
+/// [Curve::Linear]). Before the first and after the last keyframe the color of that keyframe is
+/// returned.
+#[derive(Debug, Clone)]
+pub struct Keyframed {
+    keyframes: Vec<(f32, TransparentColor<HSVColor>, Curve)>,
+}
+
+impl Keyframed {
+    /// keyframes.len() != 0, the keyframes are sorted by their percentage.
+    pub fn new(keyframes: Vec<(f32, TransparentColor<HSVColor>)>) -> Self {
+        assert!(!keyframes.is_empty(), "at least one keyframe is required");
+        let mut keyframes = keyframes
+            .into_iter()
+            .map(|(at, color)| (at, color, Curve::Linear))
+            .collect::<Vec<_>>();
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keyframes }
+    }
+
+    /// Sets the curve of the segment from keyframe `segment` to keyframe `segment + 1`.
+    pub fn with_segment_curve(mut self, segment: usize, curve: Curve) -> Self {
+        assert!(
+            segment + 1 < self.keyframes.len(),
+            "segment {} doesn't exist",
+            segment
+        );
+        self.keyframes[segment].2 = curve;
+        self
+    }
+}
+
+impl Spectrum for Keyframed {
+    type Color = HSVColor;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let percentage = clamp_percentage(percentage);
+        let next = self.keyframes.iter().position(|k| k.0 > percentage);
+        match next {
+            None => self.keyframes[self.keyframes.len() - 1].1,
+            Some(0) => self.keyframes[0].1,
+            Some(next) => {
+                let (from_at, from, curve) = &self.keyframes[next - 1];
+                let (to_at, to, _) = &self.keyframes[next];
+                let segment_percentage = (percentage - from_at) / (to_at - from_at);
+                calculate_with_curve_percentage(curve, from, to, segment_percentage)
+            }
+        }
+    }
+
+    fn is_transparent(&self) -> bool {
+        self.keyframes.iter().any(|k| !k.1.is_opaque())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.keyframes.len() == 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec;
+
+    use super::*;
+
+    fn keyframes() -> Keyframed {
+        Keyframed::new(vec![
+            (1.0, HSVColor::new(0, 100, 50).into()),
+            (0.0, HSVColor::new(0, 100, 0).into()),
+            (0.5, HSVColor::new(0, 100, 100).into()),
+        ])
+    }
+
+    #[test]
+    fn test_keyframed_interpolates_between_keyframes() {
+        let spectrum = keyframes();
+
+        assert_eq!(spectrum.color_at(0.0), HSVColor::new(0, 100, 0).into());
+        assert_eq!(spectrum.color_at(0.25), HSVColor::new(0, 100, 50).into());
+        assert_eq!(spectrum.color_at(0.5), HSVColor::new(0, 100, 100).into());
+        assert_eq!(spectrum.color_at(0.75), HSVColor::new(0, 100, 75).into());
+        assert_eq!(spectrum.color_at(1.0), HSVColor::new(0, 100, 50).into());
+    }
+
+    #[test]
+    fn test_keyframed_segment_curve() {
+        let spectrum = keyframes().with_segment_curve(0, Curve::EaseIn);
+
+        assert!(spectrum.color_at(0.25).color.v < 50);
+        assert_eq!(spectrum.color_at(0.75), HSVColor::new(0, 100, 75).into());
+    }
+}
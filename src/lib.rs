@@ -1,18 +1,25 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod animation;
 pub mod color;
 mod color_cache;
+pub mod compositor;
 pub mod controller;
 pub mod curve;
 pub mod indexing;
+pub mod keyframed;
 pub mod pattern;
 pub mod processing;
+pub mod render;
+pub mod rng;
 pub mod strip;
 pub mod timeline;
-mod util;
+pub mod units;
+pub mod util;
 
 #[cfg(test)]
 mod mock;
@@ -1,8 +1,15 @@
+use core::cell::Cell;
+
+use alloc::rc::Rc;
+use keyframe::CanTween;
+#[allow(unused_imports)]
+use num_traits::Float;
+
 use crate::{
     color::{HSVColor, Spectrum, TransparentColor},
     curve::{calculate_with_curve, Curve},
     indexing::LedId,
-    util::range_map,
+    util::{clamp_percentage, range_map_clamped},
 };
 
 pub struct Progress {
@@ -19,7 +26,7 @@ impl Progress {
     }
 
     pub fn progress(&self) -> f32 {
-        self.current_led_id as f32 / (self.len as f32 - 1.0)
+        clamp_percentage(self.current_led_id as f32 / (self.len as f32 - 1.0))
     }
 }
 
@@ -40,11 +47,12 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct HillPattern<S> {
     peak_len: u16,
     spectrum: S,
     curve: Curve,
+    fades: Option<(u16, u16)>,
 }
 
 impl<S> HillPattern<S> {
@@ -53,10 +61,67 @@ impl<S> HillPattern<S> {
             peak_len,
             spectrum,
             curve,
+            fades: None,
+        }
+    }
+
+    /// Creates a hill with explicit fade lengths, so the peak can be placed off center.
+    ///
+    /// The hill starts at the first led. Leds behind the back fade are fully transparent, if the
+    /// sum of all lengths exceeds the pattern length the end of the hill is cut.
+    pub fn with_fades(
+        front_fade: u16,
+        peak_len: u16,
+        back_fade: u16,
+        spectrum: S,
+        curve: Curve,
+    ) -> Self {
+        Self {
+            peak_len,
+            spectrum,
+            curve,
+            fades: Some((front_fade, back_fade)),
         }
     }
 }
 
+impl<S> HillPattern<S>
+where
+    S: Spectrum<Color = HSVColor>,
+{
+    fn color_with_fades(
+        &self,
+        progress: Progress,
+        front_fade: u16,
+        back_fade: u16,
+    ) -> TransparentColor<HSVColor> {
+        let peak_color = self.spectrum.color_at(0.5);
+        let led = progress.current_led_id;
+        let back_start = front_fade.saturating_add(self.peak_len);
+
+        let transparency = if led < front_fade {
+            calculate_with_curve(&self.curve, front_fade as u32, &1.0, &0.0, led as u32)
+        } else if led < back_start {
+            return peak_color;
+        } else if led - back_start < back_fade {
+            calculate_with_curve(
+                &self.curve,
+                back_fade as u32,
+                &0.0,
+                &1.0,
+                (led + 1 - back_start) as u32,
+            )
+        } else {
+            1.0
+        };
+
+        let current_color = self.spectrum.color_at(progress.progress());
+        let transparency = range_map_clamped(transparency, 0.0, 1.0, peak_color.transparency, 1.0);
+
+        TransparentColor::new(current_color.color, transparency)
+    }
+}
+
 impl<S> Pattern for HillPattern<S>
 where
     S: Spectrum<Color = HSVColor>,
@@ -64,6 +129,10 @@ where
     type Color = HSVColor;
 
     fn color_at(&self, progress: Progress) -> TransparentColor<Self::Color> {
+        if let Some((front_fade, back_fade)) = self.fades {
+            return self.color_with_fades(progress, front_fade, back_fade);
+        }
+
         let fade_len = (progress.len - self.peak_len) / 2;
 
         let peak_color = self.spectrum.color_at(0.5);
@@ -79,7 +148,8 @@ where
                 progress.current_led_id as u32,
             );
 
-            let transparency = range_map(transparency, 0.0, 1.0, peak_color.transparency, 1.0);
+            let transparency =
+                range_map_clamped(transparency, 0.0, 1.0, peak_color.transparency, 1.0);
 
             TransparentColor::new(current_color.color, transparency)
         } else if progress.current_led_id >= progress.len - fade_len {
@@ -93,7 +163,8 @@ where
                 (progress.current_led_id + 1 - self.peak_len - fade_len) as u32,
             );
 
-            let transparency = range_map(transparency, 0.0, 1.0, peak_color.transparency, 1.0);
+            let transparency =
+                range_map_clamped(transparency, 0.0, 1.0, peak_color.transparency, 1.0);
 
             TransparentColor::new(current_color.color, transparency)
         } else {
@@ -102,6 +173,116 @@ where
     }
 }
 
+/// Blends two patterns, `t` = 0.0 is pattern `a`, `t` = 1.0 is pattern `b`.
+///
+/// See [MorphAnimation](crate::animation::MorphAnimation) to drive `t` over time.
+#[derive(Debug, Clone, Copy)]
+pub struct MorphPattern<A, B> {
+    a: A,
+    b: B,
+    t: f32,
+}
+
+impl<A, B> MorphPattern<A, B> {
+    pub fn new(a: A, b: B, t: f32) -> Self {
+        Self {
+            a,
+            b,
+            t: t.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn set_t(&mut self, t: f32) {
+        self.t = t.clamp(0.0, 1.0);
+    }
+}
+
+impl<A, B, C> Pattern for MorphPattern<A, B>
+where
+    A: Pattern<Color = C>,
+    B: Pattern<Color = C>,
+    C: CanTween,
+{
+    type Color = C;
+
+    fn color_at(&self, progress: Progress) -> TransparentColor<Self::Color> {
+        let from = self
+            .a
+            .color_at(Progress::new(progress.current_led_id, progress.len));
+        let to = self.b.color_at(progress);
+        TransparentColor::ease(from, to, self.t)
+    }
+}
+
+/// Modulates the value of a color with a sine wave along the leds.
+///
+/// The value is scaled by `(sin(2π * progress / wavelength + phase) + 1) / 2`, where the
+/// wavelength is relative to the pattern length. The phase (in radians) is shared, so it can be
+/// advanced from outside to move the wave.
+#[derive(Debug, Clone)]
+pub struct WavePattern {
+    color: HSVColor,
+    wavelength: f32,
+    phase: Rc<Cell<f32>>,
+}
+
+impl WavePattern {
+    /// wavelength > 0.0
+    pub fn new(color: HSVColor, wavelength: f32, phase: Rc<Cell<f32>>) -> Self {
+        assert!(wavelength > 0.0, "wavelength must be greater than 0");
+        Self {
+            color,
+            wavelength,
+            phase,
+        }
+    }
+}
+
+impl Pattern for WavePattern {
+    type Color = HSVColor;
+
+    fn color_at(&self, progress: Progress) -> TransparentColor<Self::Color> {
+        let angle =
+            2.0 * core::f32::consts::PI * progress.progress() / self.wavelength + self.phase.get();
+        let scale = (angle.sin() + 1.0) / 2.0;
+        TransparentColor::opaque(HSVColor {
+            v: (self.color.v as f32 * scale).round() as u8,
+            ..self.color
+        })
+    }
+}
+
+/// Uses a pattern of `len` leds as [Spectrum], the percentage `p` samples the led
+/// `min(p * len, len - 1)`.
+#[derive(Debug, Clone)]
+pub struct PatternAsSpectrum<P> {
+    pattern: P,
+    len: u16,
+}
+
+impl<P> PatternAsSpectrum<P> {
+    /// len != 0
+    pub fn new(pattern: P, len: u16) -> Self {
+        assert!(len > 0, "len must be at least 1");
+        Self { pattern, len }
+    }
+}
+
+impl<P: Pattern> Spectrum for PatternAsSpectrum<P> {
+    type Color = P::Color;
+
+    fn color_at(&self, percentage: f32) -> TransparentColor<Self::Color> {
+        let led = (clamp_percentage(percentage) * self.len as f32) as LedId;
+        self.pattern
+            .color_at(Progress::new(led.min(self.len - 1), self.len))
+    }
+
+    fn is_transparent(&self) -> bool {
+        // the pattern can't tell
+        true
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -166,6 +347,79 @@ mod test {
         tester.assert(&pattern_assert);
     }
 
+    #[test]
+    fn test_hill_pattern_with_unequal_fades() {
+        let pattern_len = 10;
+        let peak_color = HSVColor::new(0, 100, 100);
+        let pattern = HillPattern::with_fades(2, 3, 4, peak_color, Curve::Linear);
+
+        let pattern_assert = [
+            peak_color.with_transparency(1.0),
+            peak_color.with_transparency(0.5),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.25),
+            peak_color.with_transparency(0.5),
+            peak_color.with_transparency(0.75),
+            peak_color.with_transparency(1.0),
+            peak_color.with_transparency(1.0),
+        ];
+
+        let tester = PatternTester::new(pattern, pattern_len);
+
+        tester.assert(&pattern_assert);
+    }
+
+    #[test]
+    fn test_hill_pattern_with_fades_exceeding_len() {
+        let peak_color = HSVColor::new(0, 100, 100);
+        let pattern = HillPattern::with_fades(2, 2, 4, peak_color, Curve::Linear);
+
+        let tester = PatternTester::new(pattern, 5);
+
+        tester.assert(&[
+            peak_color.with_transparency(1.0),
+            peak_color.with_transparency(0.5),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.25),
+        ]);
+    }
+
+    #[test]
+    fn test_morph_hill_patterns_midpoint() {
+        let pattern_len = 10;
+        let peak_color = HSVColor::new(0, 100, 100);
+        let hill = HillPattern::new(4, peak_color, Curve::Linear);
+        let plateau = HillPattern::new(10, peak_color, Curve::Linear);
+
+        let pattern_assert = [
+            peak_color.with_transparency(0.5),
+            peak_color.with_transparency(0.33),
+            peak_color.with_transparency(0.17),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.0),
+            peak_color.with_transparency(0.17),
+            peak_color.with_transparency(0.33),
+            peak_color.with_transparency(0.5),
+        ];
+
+        let tester = PatternTester::new(
+            MorphPattern::new(hill.clone(), plateau.clone(), 0.5),
+            pattern_len,
+        );
+        tester.assert(&pattern_assert);
+
+        let tester = PatternTester::new(MorphPattern::new(hill, plateau, 0.0), pattern_len);
+        tester.assert(&[
+            peak_color.with_transparency(1.0),
+            peak_color.with_transparency(0.67),
+        ]);
+    }
+
     #[test]
     fn test_hill_pattern_peak_len_2_rainbow_spectrum() {
         let pattern_len = 10;
@@ -190,4 +444,35 @@ mod test {
 
         tester.assert(&pattern_assert);
     }
+
+    #[test]
+    fn test_progress_is_clamped() {
+        assert_eq!(Progress::new(4, 5).progress(), 1.0);
+        assert_eq!(Progress::new(5, 5).progress(), 1.0);
+        assert_eq!(Progress::new(0, 1).progress(), 0.0);
+    }
+
+    #[test]
+    fn test_wave_pattern() {
+        let color = HSVColor::new(120, 100, 100);
+        let phase = Rc::new(Cell::new(0.0));
+        let tester = PatternTester::new(WavePattern::new(color, 1.0, phase.clone()), 5);
+        let with_v = |v| TransparentColor::opaque(HSVColor { v, ..color });
+
+        tester.assert(&[with_v(50), with_v(100), with_v(50), with_v(0), with_v(50)]);
+
+        phase.set(core::f32::consts::FRAC_PI_2);
+        tester.assert(&[with_v(100), with_v(50), with_v(0), with_v(50), with_v(100)]);
+    }
+
+    #[test]
+    fn test_pattern_as_spectrum() {
+        let red = HSVColor::new(0, 100, 100);
+        let hill = HillPattern::new(1, red, Curve::Linear);
+        let spectrum = PatternAsSpectrum::new(hill, 5);
+
+        assert_eq!(spectrum.color_at(0.0), red.with_transparency(1.0));
+        assert_eq!(spectrum.color_at(0.5), red.into());
+        assert_eq!(spectrum.color_at(1.0), red.with_transparency(1.0));
+    }
 }
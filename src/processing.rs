@@ -11,9 +11,34 @@ use super::{
     timeline::{Tick, Ticks, Timeline},
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessorKind {
+    Single,
+    Timeline,
+    /// A processor not provided by this crate.
+    Other,
+}
+
 pub trait Processor {
     fn update(&mut self, current_tick: Tick);
     fn has_no_work(&self) -> bool;
+
+    fn kind(&self) -> ProcessorKind {
+        ProcessorKind::Other
+    }
+
+    /// Number of animations the processor holds, 0 if unknown.
+    fn animation_count(&self) -> usize {
+        0
+    }
+
+    /// Name of the processed animation, if it has one.
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Resets the processed animations, see [Animation::reset](crate::animation::Animation::reset).
+    fn reset(&mut self) {}
 }
 
 pub struct TimelineProcessor<A, T, S> {
@@ -45,7 +70,12 @@ where
     S: Strip + 'static,
 {
     fn update(&mut self, current_tick: Tick) {
-        if self.timeline.has_finished(current_tick - self.tick_offset) {
+        let tick = match current_tick.checked_sub(self.tick_offset) {
+            Some(tick) => tick,
+            // restored to a tick before the current iteration started
+            None => return,
+        };
+        if self.timeline.has_finished(tick) {
             if self.timeline.should_repeat() {
                 self.tick_offset = current_tick;
                 self.iteration_index += 1;
@@ -77,12 +107,29 @@ where
     fn has_no_work(&self) -> bool {
         self.no_work
     }
+
+    fn kind(&self) -> ProcessorKind {
+        ProcessorKind::Timeline
+    }
+
+    fn animation_count(&self) -> usize {
+        self.timeline.animation_count()
+    }
+
+    fn reset(&mut self) {
+        self.timeline.reset(self.strip.clone());
+    }
 }
 
+/// Renders the ticks `start..=start + duration` of a timed animation, the processor has no work
+/// once a later tick is updated.
+///
+/// If the last tick was skipped, e.g. with a speed above 1, it's rendered before finishing.
 pub struct SingleAnimationProcessor<A, S> {
     animation: A,
     strip: Rc<RefCell<S>>,
     has_finished: bool,
+    rendered_last_tick: bool,
 }
 
 impl<A, S> SingleAnimationProcessor<A, S> {
@@ -91,24 +138,19 @@ impl<A, S> SingleAnimationProcessor<A, S> {
             animation,
             strip,
             has_finished: false,
+            rendered_last_tick: false,
         }
     }
 }
 
-impl<A, S> Processor for SingleAnimationProcessor<A, S>
+impl<A, S> SingleAnimationProcessor<A, S>
 where
     A: TimedAnimationAt<S> + 'static,
     S: Strip + 'static,
 {
-    fn update(&mut self, current_tick: Tick) {
-        let start = self.animation.at_tick();
-        if start + self.animation.duration() > current_tick {
-            self.has_finished = true;
-            return;
-        }
-
+    fn render(&mut self, animation_tick: Tick) {
         let animation_step = self.animation.animate(
-            current_tick - start,
+            animation_tick,
             self.strip.clone(),
             &AnimationMeta::new(IterationState::single()),
         );
@@ -118,9 +160,134 @@ where
                 .borrow_mut()
                 .set_led_to_color(coloring.led, &coloring.color.into())
         }
+        self.rendered_last_tick = animation_tick == self.animation.duration();
+    }
+}
+
+impl<A, S> Processor for SingleAnimationProcessor<A, S>
+where
+    A: TimedAnimationAt<S> + 'static,
+    S: Strip + 'static,
+{
+    fn update(&mut self, current_tick: Tick) {
+        let start = self.animation.at_tick();
+        let duration = self.animation.duration();
+        let animation_tick = match current_tick.checked_sub(start) {
+            Some(tick) => tick,
+            // restored to a tick before the start, the animation hasn't started yet
+            None => return,
+        };
+        if current_tick > start + duration {
+            if !self.rendered_last_tick {
+                self.render(duration);
+            }
+            self.has_finished = true;
+            return;
+        }
+
+        self.render(animation_tick);
     }
 
     fn has_no_work(&self) -> bool {
         self.has_finished
     }
+
+    fn kind(&self) -> ProcessorKind {
+        ProcessorKind::Single
+    }
+
+    fn animation_count(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        self.animation.name()
+    }
+
+    fn reset(&mut self) {
+        self.animation.reset(self.strip.clone());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{boxed::Box, vec::Vec};
+
+    use crate::{
+        animation::{Animation, TimedAnimation},
+        color::{HSVColor, LedColoring},
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    struct TickRecorder(Rc<RefCell<Vec<Tick>>>);
+
+    impl<S: Strip> Animation<S> for TickRecorder {
+        fn animate(
+            &self,
+            animation_tick: Tick,
+            _: Rc<RefCell<S>>,
+            _: &AnimationMeta,
+        ) -> Box<dyn Iterator<Item = LedColoring<HSVColor>>> {
+            self.0.borrow_mut().push(animation_tick);
+            Box::new(core::iter::empty())
+        }
+
+        fn duration(&self) -> Ticks {
+            3
+        }
+    }
+
+    struct ExternalProcessor;
+
+    impl Processor for ExternalProcessor {
+        fn update(&mut self, _: Tick) {}
+
+        fn has_no_work(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_processor_defaults() {
+        assert_eq!(ExternalProcessor.kind(), ProcessorKind::Other);
+        assert_eq!(ExternalProcessor.animation_count(), 0);
+        assert_eq!(ExternalProcessor.name(), None);
+    }
+
+    #[test]
+    fn test_single_animation_processor_renders_last_tick() {
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let mut processor = SingleAnimationProcessor::new(
+            TimedAnimation::new(2, TickRecorder(ticks.clone())),
+            strip,
+        );
+
+        for tick in 2..=5 {
+            processor.update(tick);
+            assert!(!processor.has_no_work(), "tick: {}", tick);
+        }
+        processor.update(6);
+        assert!(processor.has_no_work());
+        assert_eq!(*ticks.borrow(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_single_animation_processor_renders_skipped_last_tick() {
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let strip = Rc::new(RefCell::new(LedStrip::<SPI, 4>::new()));
+        let mut processor = SingleAnimationProcessor::new(
+            TimedAnimation::new(0, TickRecorder(ticks.clone())),
+            strip,
+        );
+
+        processor.update(0);
+        processor.update(2);
+        processor.update(10);
+        assert!(processor.has_no_work());
+        assert_eq!(*ticks.borrow(), [0, 2, 3]);
+    }
 }
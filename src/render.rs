@@ -0,0 +1,140 @@
+use core::cell::RefCell;
+
+use alloc::{rc::Rc, vec::Vec};
+
+use crate::{
+    animation::TimedAnimationAt,
+    color::Color,
+    controller::{AnimationController, StartingPoint},
+    indexing::LedId,
+    strip::Strip,
+    timeline::{Ticks, Timeline},
+};
+
+/// In memory strip which records a snapshot of all leds every time [Strip::update_leds] is called.
+#[derive(Debug)]
+pub struct RecordingStrip<const N: usize> {
+    leds: [Color; N],
+    frames: Vec<Vec<Color>>,
+}
+
+impl<const N: usize> RecordingStrip<N> {
+    pub fn new() -> Self {
+        Self {
+            leds: [Color::off(); N],
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn frames(&self) -> &[Vec<Color>] {
+        &self.frames
+    }
+
+    pub fn take_frames(&mut self) -> Vec<Vec<Color>> {
+        core::mem::take(&mut self.frames)
+    }
+}
+
+impl<const N: usize> Default for RecordingStrip<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Strip for RecordingStrip<N> {
+    const LED_AMOUNT: usize = N;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        self.leds[usize::from(led_id)] = *color;
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.frames.push(self.leds.to_vec());
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.leds[usize::from(led_id)]
+    }
+}
+
+/// Runs the timeline headless for the given amount of ticks and returns every rendered frame.
+///
+/// Useful to compare a whole show against a golden sequence of frames.
+pub fn run_to_recording<const N: usize, T, A>(timeline: T, ticks: Ticks) -> Vec<Vec<Color>>
+where
+    A: TimedAnimationAt<RecordingStrip<N>> + 'static,
+    T: Timeline<RecordingStrip<N>, A> + 'static,
+{
+    let strip = Rc::new(RefCell::new(RecordingStrip::<N>::new()));
+    let mut controller = AnimationController::new(strip.clone());
+    controller
+        .queue_timeline(timeline, StartingPoint::Now)
+        .expect("controller has no processor limit");
+
+    for _ in 0..ticks {
+        controller.update();
+        strip.borrow_mut().update_leds();
+    }
+
+    let frames = strip.borrow_mut().take_frames();
+    frames
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::StaticAnimation,
+        color::{BlendMode, Color, HSVColor},
+        curve::Curve,
+        timeline::DynTimelineBuilder,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_run_to_recording() {
+        let timeline = DynTimelineBuilder::new()
+            .add_animation(
+                0,
+                StaticAnimation::new(
+                    2,
+                    0..3,
+                    HSVColor::red(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+            )
+            .add_animation(
+                2,
+                StaticAnimation::new(
+                    2,
+                    3..6,
+                    HSVColor::blue(),
+                    Curve::Step,
+                    BlendMode::AllChannels,
+                ),
+            )
+            .finish();
+
+        let frames = run_to_recording::<6, _, _>(timeline, 6);
+
+        assert_eq!(frames.len(), 6);
+        assert_eq!(
+            frames.last().unwrap().as_slice(),
+            &[
+                Color::red(),
+                Color::red(),
+                Color::red(),
+                Color::blue(),
+                Color::blue(),
+                Color::blue()
+            ]
+        );
+    }
+}
@@ -0,0 +1,100 @@
+/// Small deterministic pseudo random number generator (xoshiro128**) for random effects.
+///
+/// The same seed always produces the same sequence, which keeps random animations reproducible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xoshiro128 {
+    state: [u32; 4],
+}
+
+impl Xoshiro128 {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: [0; 4] };
+        rng.seed(seed);
+        rng
+    }
+
+    /// Resets the generator to the start of the sequence of the given seed.
+    pub fn seed(&mut self, seed: u64) {
+        let mut splitmix_state = seed;
+        let a = splitmix64(&mut splitmix_state);
+        let b = splitmix64(&mut splitmix_state);
+        self.state = [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32];
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 9;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(11);
+
+        result
+    }
+
+    /// Returns a value in range 0.0..1.0.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Xoshiro128::new(42);
+        let mut b = Xoshiro128::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+
+        a.seed(7);
+        b.seed(7);
+        assert_eq!(a.next_f32(), b.next_f32());
+    }
+
+    #[test]
+    fn test_different_seed_different_sequence() {
+        let mut a = Xoshiro128::new(1);
+        let mut b = Xoshiro128::new(2);
+
+        let same = (0..100).filter(|_| a.next_u32() == b.next_u32()).count();
+        assert!(same < 5);
+    }
+
+    #[test]
+    fn test_f32_uniform_distribution() {
+        let mut rng = Xoshiro128::new(1234);
+        let mut buckets = [0u32; 10];
+
+        for _ in 0..10_000 {
+            let value = rng.next_f32();
+            assert!((0.0..1.0).contains(&value));
+            buckets[(value * 10.0) as usize] += 1;
+        }
+
+        for (i, count) in buckets.iter().enumerate() {
+            assert!(
+                (800..1200).contains(count),
+                "bucket {} has {} samples",
+                i,
+                count
+            );
+        }
+    }
+}
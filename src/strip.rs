@@ -1,4 +1,16 @@
-use crate::{color::Color, indexing::LedId};
+use alloc::vec::Vec;
+#[allow(unused_imports)]
+use num_traits::Float;
+
+use crate::{
+    color::{Color, HSVColor},
+    indexing::LedId,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StripError {
+    LedIdOutOfRange(LedId),
+}
 
 pub trait Strip {
     const LED_AMOUNT: usize;
@@ -6,6 +18,421 @@ pub trait Strip {
     fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color);
     fn update_leds(&mut self);
     fn get_color_of_led(&self, led_id: LedId) -> Color;
+
+    /// Same as [Strip::set_led_to_color], but returns an error instead of panicking if the led id
+    /// is not on the strip.
+    fn try_set_led_to_color(&mut self, led_id: LedId, color: &Color) -> Result<(), StripError> {
+        if usize::from(led_id) >= Self::LED_AMOUNT {
+            return Err(StripError::LedIdOutOfRange(led_id));
+        }
+        self.set_led_to_color(led_id, color);
+        Ok(())
+    }
+}
+
+pub trait StripExt: Strip {
+    /// Replaces the color of every led with its RGB complement, see [Color::inverted].
+    fn invert_all(&mut self) {
+        for led_id in 0..Self::LED_AMOUNT {
+            let led_id = LedId::try_from(led_id).unwrap();
+            let inverted = self.get_color_of_led(led_id).inverted();
+            self.set_led_to_color(led_id, &inverted);
+        }
+    }
+}
+
+impl<S: Strip> StripExt for S {}
+
+/// Estimates the current a single led draws when showing a color.
+pub trait PowerModel {
+    fn current_ma(&self, color: &Color) -> f32;
+}
+
+/// Power model which assumes every channel draws linearly to its value.
+///
+/// The currents are specified per channel at full brightness (255).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearPowerModel {
+    pub red_ma: f32,
+    pub green_ma: f32,
+    pub blue_ma: f32,
+}
+
+impl LinearPowerModel {
+    pub const fn new(red_ma: f32, green_ma: f32, blue_ma: f32) -> Self {
+        Self {
+            red_ma,
+            green_ma,
+            blue_ma,
+        }
+    }
+}
+
+impl Default for LinearPowerModel {
+    /// Typical values of a WS2812 led.
+    fn default() -> Self {
+        Self::new(20.0, 20.0, 20.0)
+    }
+}
+
+impl PowerModel for LinearPowerModel {
+    fn current_ma(&self, color: &Color) -> f32 {
+        (color[0] as f32 * self.red_ma
+            + color[1] as f32 * self.green_ma
+            + color[2] as f32 * self.blue_ma)
+            / 255.0
+    }
+}
+
+/// Limits the current drawn by the whole strip.
+///
+/// Colors are buffered until [Strip::update_leds] is called. If the estimated current of the
+/// buffered frame exceeds `max_current_ma`, all leds are scaled down evenly before they are
+/// written to the inner strip.
+pub struct PowerLimitedStrip<S, M> {
+    strip: S,
+    model: M,
+    max_current_ma: f32,
+    leds: Vec<Color>,
+}
+
+impl<S: Strip, M: PowerModel> PowerLimitedStrip<S, M> {
+    pub fn new(strip: S, model: M, max_current_ma: f32) -> Self {
+        Self {
+            strip,
+            model,
+            max_current_ma,
+            leds: alloc::vec![Color::off(); S::LED_AMOUNT],
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+
+    /// Estimated current of the buffered frame, before limiting.
+    pub fn current_ma(&self) -> f32 {
+        self.leds.iter().map(|c| self.model.current_ma(c)).sum()
+    }
+}
+
+impl<S: Strip, M: PowerModel> Strip for PowerLimitedStrip<S, M> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        self.leds[usize::from(led_id)] = *color;
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        let current = self.current_ma();
+        let scale = if current > self.max_current_ma {
+            self.max_current_ma / current
+        } else {
+            1.0
+        };
+
+        for (led_id, color) in self.leds.iter().enumerate() {
+            self.strip
+                .set_led_to_color(LedId::try_from(led_id).unwrap(), &(*color * scale));
+        }
+        self.strip.update_leds();
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.leds[usize::from(led_id)]
+    }
+}
+
+/// Smooths the output of every led over time.
+///
+/// Each written color is blended with the previously displayed color of the led, where
+/// `smoothing` is the weight of the previous color (0 = instant, near 1 = very slow).
+pub struct SmoothedStrip<S> {
+    strip: S,
+    smoothing: f32,
+    previous: Vec<Color>,
+}
+
+impl<S: Strip> SmoothedStrip<S> {
+    /// 0 <= smoothing < 1
+    pub fn new(strip: S, smoothing: f32) -> Self {
+        assert!(
+            (0.0..1.0).contains(&smoothing),
+            "smoothing must be in range 0..1"
+        );
+        let previous = (0..S::LED_AMOUNT)
+            .map(|led_id| strip.get_color_of_led(LedId::try_from(led_id).unwrap()))
+            .collect();
+        Self {
+            strip,
+            smoothing,
+            previous,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+}
+
+impl<S: Strip> Strip for SmoothedStrip<S> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        let previous = &mut self.previous[usize::from(led_id)];
+        for channel in 0..3 {
+            let smoothed = previous[channel] as f32 * self.smoothing
+                + color[channel] as f32 * (1.0 - self.smoothing);
+            previous[channel] = (smoothed + 0.5) as u8;
+        }
+        self.strip.set_led_to_color(led_id, previous);
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.strip.update_leds();
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.previous[usize::from(led_id)]
+    }
+}
+
+/// Limits how fast the value of every led can change.
+///
+/// Unlike [SmoothedStrip] the change is capped linearly, the value channel of a led moves by at
+/// most `max_delta` per written color. Hue and saturation are applied immediately.
+pub struct SlewLimitedStrip<S> {
+    strip: S,
+    max_delta: u8,
+    previous: Vec<HSVColor>,
+}
+
+impl<S: Strip> SlewLimitedStrip<S> {
+    pub fn new(strip: S, max_delta: u8) -> Self {
+        let previous = (0..S::LED_AMOUNT)
+            .map(|led_id| {
+                strip
+                    .get_color_of_led(LedId::try_from(led_id).unwrap())
+                    .into()
+            })
+            .collect();
+        Self {
+            strip,
+            max_delta,
+            previous,
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+}
+
+impl<S: Strip> Strip for SlewLimitedStrip<S> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        let target = HSVColor::from(*color);
+        let previous = &mut self.previous[usize::from(led_id)];
+        let v = if target.v > previous.v {
+            target.v.min(previous.v.saturating_add(self.max_delta))
+        } else {
+            target.v.max(previous.v.saturating_sub(self.max_delta))
+        };
+        *previous = HSVColor { v, ..target };
+        self.strip.set_led_to_color(led_id, &(*previous).into());
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.strip.update_leds();
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.previous[usize::from(led_id)].into()
+    }
+}
+
+/// Reduces every RGB channel to its top `bits` bits before forwarding, e.g. for strips with a
+/// limited color depth.
+pub struct BitDepthStrip<S> {
+    strip: S,
+    mask: u8,
+}
+
+impl<S: Strip> BitDepthStrip<S> {
+    /// 1 <= bits <= 8
+    pub fn new(strip: S, bits: u8) -> Self {
+        assert!((1..=8).contains(&bits), "bits must be in range 1..=8");
+        Self {
+            strip,
+            mask: 0xff << (8 - bits),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+}
+
+impl<S: Strip> Strip for BitDepthStrip<S> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        let mut quantized = *color;
+        for channel in 0..3 {
+            quantized[channel] &= self.mask;
+        }
+        self.strip.set_led_to_color(led_id, &quantized);
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.strip.update_leds();
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.strip.get_color_of_led(led_id)
+    }
+}
+
+/// Tracks which leds were written since the last [Strip::update_leds].
+///
+/// Hardware drivers can use the dirty leds to only transfer the changed part of the strip.
+pub struct DirtyTrackingStrip<S> {
+    strip: S,
+    dirty: Vec<LedId>,
+    // membership of dirty per led, so a write doesn't need to search the list
+    is_dirty: Vec<bool>,
+}
+
+impl<S: Strip> DirtyTrackingStrip<S> {
+    pub fn new(strip: S) -> Self {
+        Self {
+            strip,
+            dirty: Vec::new(),
+            is_dirty: alloc::vec![false; S::LED_AMOUNT],
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+
+    /// Leds written since the last flush, in order of their first write.
+    pub fn dirty(&self) -> &[LedId] {
+        &self.dirty
+    }
+}
+
+impl<S: Strip> Strip for DirtyTrackingStrip<S> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        let is_dirty = &mut self.is_dirty[usize::from(led_id)];
+        if !*is_dirty {
+            *is_dirty = true;
+            self.dirty.push(led_id);
+        }
+        self.strip.set_led_to_color(led_id, color);
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.strip.update_leds();
+        for led_id in self.dirty.drain(..) {
+            self.is_dirty[usize::from(led_id)] = false;
+        }
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.strip.get_color_of_led(led_id)
+    }
+}
+
+/// Darkens leds towards the physical ends of the strip.
+///
+/// The vignette covers the outer `width` (0..=1) of each half of the strip, where the value falls
+/// off linearly until it is reduced by `strength` (0..=1) at the outermost led.
+pub struct VignetteStrip<S> {
+    strip: S,
+    strength: f32,
+    width: f32,
+}
+
+impl<S: Strip> VignetteStrip<S> {
+    pub fn new(strip: S, strength: f32, width: f32) -> Self {
+        Self {
+            strip,
+            strength: strength.clamp(0.0, 1.0),
+            width: width.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.strip
+    }
+
+    /// Scale applied to the led, 1 means unchanged.
+    pub fn falloff(&self, led_id: LedId) -> f32 {
+        let center = S::LED_AMOUNT.saturating_sub(1) as f32 / 2.0;
+        if center == 0.0 || self.width == 0.0 {
+            return 1.0;
+        }
+        let distance = (f32::from(led_id) - center).abs() / center;
+        let edge = ((distance - (1.0 - self.width)) / self.width).clamp(0.0, 1.0);
+        1.0 - self.strength * edge
+    }
+}
+
+impl<S: Strip> Strip for VignetteStrip<S> {
+    const LED_AMOUNT: usize = S::LED_AMOUNT;
+
+    fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+        let scaled = *color * self.falloff(led_id);
+        self.strip.set_led_to_color(led_id, &scaled);
+    }
+
+    fn set_leds_to_color(&mut self, led_ids: &[LedId], color: &Color) {
+        led_ids
+            .iter()
+            .for_each(|led_id| self.set_led_to_color(*led_id, color))
+    }
+
+    fn update_leds(&mut self) {
+        self.strip.update_leds();
+    }
+
+    fn get_color_of_led(&self, led_id: LedId) -> Color {
+        self.strip.get_color_of_led(led_id)
+    }
 }
 
 #[cfg(test)]
@@ -34,6 +461,12 @@ pub(crate) mod mock {
         const LED_AMOUNT: usize = N;
 
         fn set_led_to_color(&mut self, led_id: LedId, color: &Color) {
+            assert!(
+                usize::from(led_id) < N,
+                "led id {} is out of range, LED_AMOUNT is {}",
+                led_id,
+                N
+            );
             self.leds[usize::from(led_id)] = *color;
         }
 
@@ -56,3 +489,181 @@ pub(crate) mod mock {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{color::Color, mock::SPI};
+
+    use super::{mock::LedStrip, *};
+
+    struct RedOnlyModel;
+
+    impl PowerModel for RedOnlyModel {
+        fn current_ma(&self, color: &Color) -> f32 {
+            color[0] as f32
+        }
+    }
+
+    #[test]
+    fn test_invert_all() {
+        let mut strip = LedStrip::<SPI, 3>::new();
+        strip.set_led_to_color(0, &Color::red());
+        strip.set_led_to_color(1, &Color::white());
+
+        strip.invert_all();
+
+        assert_eq!(strip.get_color_of_led(0), Color::init(0, 255, 255));
+        assert_eq!(strip.get_color_of_led(1), Color::off());
+        assert_eq!(strip.get_color_of_led(2), Color::white());
+    }
+
+    #[test]
+    fn test_power_limited_strip_custom_model() {
+        let mut strip = PowerLimitedStrip::new(LedStrip::<SPI, 4>::new(), RedOnlyModel, 255.0);
+
+        strip.set_leds_to_color(&[0, 1], &Color::red());
+        strip.set_leds_to_color(&[2, 3], &Color::blue());
+        strip.update_leds();
+
+        assert_eq!(strip.current_ma(), 510.0);
+        assert_eq!(strip.inner().get_color_of_led(0), Color::init(127, 0, 0));
+        assert_eq!(strip.inner().get_color_of_led(2), Color::init(0, 0, 127));
+        assert_eq!(strip.get_color_of_led(0), Color::red());
+
+        strip.set_leds_to_color(&[0, 1], &Color::off());
+        strip.update_leds();
+
+        assert_eq!(strip.inner().get_color_of_led(2), Color::blue());
+    }
+
+    #[test]
+    fn test_linear_power_model() {
+        let model = LinearPowerModel::default();
+
+        assert_eq!(model.current_ma(&Color::white()), 60.0);
+        assert_eq!(model.current_ma(&Color::red()), 20.0);
+        assert_eq!(model.current_ma(&Color::off()), 0.0);
+    }
+
+    #[test]
+    fn test_smoothed_strip_approaches_target() {
+        let mut strip = SmoothedStrip::new(LedStrip::<SPI, 2>::new(), 0.5);
+
+        let mut last = 0;
+        for _ in 0..3 {
+            strip.set_led_to_color(0, &Color::white());
+            let red = strip.inner().get_color_of_led(0)[0];
+            assert!(
+                red > last && red < 255,
+                "{} should be in ({}, 255)",
+                red,
+                last
+            );
+            last = red;
+        }
+
+        for _ in 0..10 {
+            strip.set_led_to_color(0, &Color::white());
+        }
+        assert_eq!(strip.inner().get_color_of_led(0), Color::white());
+        assert_eq!(strip.get_color_of_led(1), Color::off());
+    }
+
+    #[test]
+    fn test_smoothed_strip_instant() {
+        let mut strip = SmoothedStrip::new(LedStrip::<SPI, 2>::new(), 0.0);
+
+        strip.set_led_to_color(0, &Color::white());
+        assert_eq!(strip.inner().get_color_of_led(0), Color::white());
+    }
+
+    #[test]
+    fn test_slew_limited_strip() {
+        let mut strip = SlewLimitedStrip::new(LedStrip::<SPI, 2>::new(), 10);
+
+        for frame in 1..=10 {
+            strip.set_led_to_color(0, &Color::white());
+            assert_eq!(
+                strip.inner().get_color_of_led(0),
+                HSVColor::new(0, 0, frame * 10).into()
+            );
+        }
+        assert_eq!(strip.inner().get_color_of_led(0), Color::white());
+
+        strip.set_led_to_color(0, &Color::off());
+        assert_eq!(strip.get_color_of_led(0), HSVColor::new(0, 0, 90).into());
+        assert_eq!(strip.get_color_of_led(1), Color::off());
+    }
+
+    #[test]
+    fn test_bit_depth_strip() {
+        let mut strip = BitDepthStrip::new(LedStrip::<SPI, 2>::new(), 2);
+
+        strip.set_led_to_color(0, &Color::init(63, 64, 200));
+        assert_eq!(strip.get_color_of_led(0), Color::init(0, 64, 192));
+
+        strip.set_led_to_color(1, &Color::init(255, 128, 127));
+        assert_eq!(strip.get_color_of_led(1), Color::init(192, 128, 64));
+
+        let mut full = BitDepthStrip::new(LedStrip::<SPI, 1>::new(), 8);
+        full.set_led_to_color(0, &Color::init(1, 127, 255));
+        assert_eq!(full.get_color_of_led(0), Color::init(1, 127, 255));
+    }
+
+    #[test]
+    fn test_dirty_tracking_strip() {
+        let mut strip = DirtyTrackingStrip::new(LedStrip::<SPI, 6>::new());
+        assert!(strip.dirty().is_empty());
+
+        strip.set_led_to_color(4, &Color::red());
+        strip.set_leds_to_color(&[1, 4, 2], &Color::blue());
+
+        assert_eq!(strip.dirty(), &[4, 1, 2]);
+        assert_eq!(strip.get_color_of_led(4), Color::blue());
+
+        strip.update_leds();
+        assert!(strip.dirty().is_empty());
+
+        strip.set_led_to_color(2, &Color::red());
+        strip.set_led_to_color(2, &Color::blue());
+        assert_eq!(strip.dirty(), &[2]);
+    }
+
+    #[test]
+    fn test_vignette_strip() {
+        let mut strip = VignetteStrip::new(LedStrip::<SPI, 9>::new(), 1.0, 0.5);
+        let leds: Vec<LedId> = (0..9).collect();
+        strip.set_leds_to_color(&leds, &Color::white());
+
+        assert_eq!(strip.get_color_of_led(4), Color::white());
+        assert_eq!(strip.get_color_of_led(3), Color::white());
+        assert_eq!(strip.get_color_of_led(0), Color::off());
+        assert_eq!(strip.get_color_of_led(8), Color::off());
+        let near_end = strip.get_color_of_led(1)[0];
+        assert!(
+            near_end > 0 && near_end < 255,
+            "{} should be dimmed",
+            near_end
+        );
+        assert_eq!(strip.get_color_of_led(1), strip.get_color_of_led(7));
+    }
+
+    #[test]
+    #[should_panic(expected = "led id 6 is out of range, LED_AMOUNT is 6")]
+    fn test_set_led_out_of_range_panics() {
+        let mut strip = LedStrip::<SPI, 6>::new();
+        strip.set_led_to_color(6, &Color::red());
+    }
+
+    #[test]
+    fn test_try_set_led_to_color() {
+        let mut strip = LedStrip::<SPI, 6>::new();
+
+        assert_eq!(strip.try_set_led_to_color(5, &Color::red()), Ok(()));
+        assert_eq!(strip.get_color_of_led(5), Color::red());
+        assert_eq!(
+            strip.try_set_led_to_color(6, &Color::red()),
+            Err(StripError::LedIdOutOfRange(6))
+        );
+    }
+}
@@ -1,3 +1,10 @@
+use core::{
+    cell::{Cell, RefCell},
+    ops::Range,
+};
+
+use alloc::rc::Rc;
+
 use crate::{
     animation::{Animation, BoxedAnimation, TimedAnimationAt, TimedAt},
     strip::Strip,
@@ -20,6 +27,14 @@ where
     fn get_current_entries(&self, current_tick: Tick) -> Self::Iter<'_>;
     fn has_finished(&self, current_tick: Tick) -> bool;
     fn should_repeat(&self) -> bool;
+
+    /// Number of animations in the timeline, 0 if unknown.
+    fn animation_count(&self) -> usize {
+        0
+    }
+
+    /// Resets every animation of the timeline, see [Animation::reset].
+    fn reset(&self, _strip: Rc<RefCell<S>>) {}
 }
 
 #[derive(Default)]
@@ -75,6 +90,65 @@ impl<S> DynTimeline<S> {
         let entries = { alloc::vec::Vec::new() };
         Self { entries, repeating }
     }
+
+    /// Shifts the start of all entries by the given ticks, starts before 0 are clamped to 0.
+    pub fn shift(&mut self, by: i32) {
+        for entry in self.entries.iter_mut() {
+            entry.0 = (i64::from(entry.0) + i64::from(by)).max(0) as Tick;
+        }
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+}
+
+impl<S> DynTimeline<S>
+where
+    S: Strip,
+{
+    /// Tick at which the last entry of the timeline has finished.
+    pub fn total_duration(&self) -> Ticks {
+        self.entries
+            .iter()
+            .map(|e| e.0 + e.animation_duration())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Tick ranges between 0 and [total_duration](Self::total_duration) in which no entry is
+    /// active, an entry covers the ticks `start..start + duration`.
+    pub fn coverage_gaps(&self) -> alloc::vec::Vec<Range<Tick>> {
+        let mut gaps = alloc::vec::Vec::new();
+        let mut covered_until = 0;
+        // entries are sorted by start
+        for (start, duration) in self.entries() {
+            if start > covered_until {
+                gaps.push(covered_until..start);
+            }
+            covered_until = covered_until.max(start + duration);
+        }
+        gaps
+    }
+
+    /// Start tick and duration of every entry, ordered by start.
+    pub fn entries(&self) -> impl Iterator<Item = (Tick, Ticks)> + '_ {
+        self.entries.iter().map(|e| (e.0, e.animation_duration()))
+    }
+
+    /// Appends the entries of `other`, starting `gap` ticks after this timeline has finished.
+    pub fn concat(mut self, mut other: DynTimeline<S>, gap: Ticks) -> Self {
+        let offset = self.total_duration() + gap;
+        for entry in other.entries.iter_mut() {
+            entry.0 += offset;
+        }
+        self.entries.append(&mut other.entries);
+        self
+    }
+
+    /// Merges the entries of `other` at their own start ticks.
+    pub fn overlay(mut self, mut other: DynTimeline<S>) -> Self {
+        self.entries.append(&mut other.entries);
+        self.entries.sort_by(|a, b| a.0.cmp(&b.0));
+        self
+    }
 }
 
 pub struct DynTimelineIter<'a, S> {
@@ -85,11 +159,11 @@ pub struct DynTimelineIter<'a, S> {
 
 impl<'a, S> DynTimelineIter<'a, S> {
     pub(crate) fn new(
-        animations: &'a alloc::vec::Vec<TimedAnimation<crate::animation::BoxedAnimation<S>, S>>,
+        animations: &'a [TimedAnimation<crate::animation::BoxedAnimation<S>, S>],
         within_tick: Tick,
     ) -> Self {
         Self {
-            s: animations.as_slice(),
+            s: animations,
             act_index: 0,
             within_tick,
         }
@@ -146,4 +220,264 @@ where
     fn should_repeat(&self) -> bool {
         self.repeating
     }
+
+    fn animation_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        for entry in self.entries.iter().rev() {
+            entry.reset(strip.clone());
+        }
+    }
+}
+
+/// Repeatedly plays one out of several animations, chosen by an externally set index.
+///
+/// A changed selection takes effect when the currently playing animation has finished. An index
+/// out of range selects the last animation.
+pub struct SelectTimeline<S> {
+    entries: alloc::vec::Vec<TimedAnimation<BoxedAnimation<S>, S>>,
+    selected: Rc<Cell<usize>>,
+    active: Cell<usize>,
+}
+
+impl<S> SelectTimeline<S>
+where
+    S: Strip + 'static,
+{
+    pub fn new(selected: Rc<Cell<usize>>) -> Self {
+        let active = Cell::new(selected.get());
+        Self {
+            entries: alloc::vec::Vec::new(),
+            selected,
+            active,
+        }
+    }
+
+    pub fn add_animation<A>(mut self, animation: A) -> Self
+    where
+        A: Animation<S> + 'static,
+    {
+        self.entries
+            .push(TimedAnimation::new(0, alloc::boxed::Box::new(animation)));
+        self
+    }
+
+    fn active_entry(&self) -> &[TimedAnimation<BoxedAnimation<S>, S>] {
+        match self.entries.len().checked_sub(1) {
+            Some(last) => core::slice::from_ref(&self.entries[self.active.get().min(last)]),
+            None => &[],
+        }
+    }
+}
+
+impl<S> Timeline<S, TimedAnimation<BoxedAnimation<S>, S>> for SelectTimeline<S>
+where
+    S: Strip + 'static,
+{
+    type Iter<'a> = DynTimelineIter<'a, S>;
+
+    fn get_current_entries(&self, current_tick: Tick) -> Self::Iter<'_> {
+        DynTimelineIter::new(self.active_entry(), current_tick)
+    }
+
+    fn has_finished(&self, act_tick: Tick) -> bool {
+        let finished = match self.active_entry().first() {
+            Some(e) => e.0 + e.1.duration() < act_tick,
+            None => true,
+        };
+        if finished {
+            self.active.set(self.selected.get());
+        }
+        finished
+    }
+
+    fn should_repeat(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    fn animation_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn reset(&self, strip: Rc<RefCell<S>>) {
+        for entry in self.entries.iter().rev() {
+            entry.reset(strip.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::StaticAnimation,
+        color::{BlendMode, HSVColor},
+        curve::Curve,
+        mock::SPI,
+        strip::mock::LedStrip,
+    };
+
+    use super::*;
+
+    type TestStrip = LedStrip<SPI, 6>;
+
+    fn static_animation(duration: Ticks) -> StaticAnimation<core::ops::Range<u16>, HSVColor> {
+        StaticAnimation::new(
+            duration,
+            0..6,
+            HSVColor::red(),
+            Curve::Linear,
+            BlendMode::AllChannels,
+        )
+    }
+
+    #[test]
+    fn test_shift_timeline() {
+        let mut timeline = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(0, static_animation(10))
+            .add_animation(5, static_animation(10))
+            .finish();
+        assert_eq!(timeline.total_duration(), 15);
+
+        timeline.shift(20);
+
+        let starts = timeline
+            .entries
+            .iter()
+            .map(|e| e.0)
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(starts, [20, 25]);
+        assert_eq!(timeline.total_duration(), 35);
+    }
+
+    #[test]
+    fn test_shift_timeline_clamps_at_zero() {
+        let mut timeline = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(2, static_animation(10))
+            .add_animation(8, static_animation(10))
+            .finish();
+
+        timeline.shift(-5);
+
+        let starts = timeline
+            .entries
+            .iter()
+            .map(|e| e.0)
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(starts, [0, 3]);
+        assert_eq!(timeline.total_duration(), 13);
+    }
+
+    #[test]
+    fn test_timeline_entries() {
+        let timeline = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(12, static_animation(3))
+            .add_animation(0, static_animation(10))
+            .add_animation(5, static_animation(7))
+            .finish();
+
+        let entries = timeline.entries().collect::<alloc::vec::Vec<_>>();
+        assert_eq!(entries, [(0, 10), (5, 7), (12, 3)]);
+        assert_eq!(DynTimeline::<TestStrip>::new(false).entries().count(), 0);
+    }
+
+    #[test]
+    fn test_concat_timelines() {
+        let first = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(0, static_animation(10))
+            .add_animation(5, static_animation(7))
+            .finish();
+        let second = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(0, static_animation(4))
+            .add_animation(2, static_animation(3))
+            .finish();
+
+        let timeline = first.concat(second, 3);
+
+        let entries = timeline.entries().collect::<alloc::vec::Vec<_>>();
+        assert_eq!(entries, [(0, 10), (5, 7), (15, 4), (17, 3)]);
+        assert_eq!(timeline.total_duration(), 20);
+    }
+
+    #[test]
+    fn test_overlay_timelines() {
+        let first = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(0, static_animation(10))
+            .add_animation(8, static_animation(7))
+            .finish();
+        let second = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(3, static_animation(4))
+            .add_animation(12, static_animation(3))
+            .finish();
+
+        let timeline = first.overlay(second);
+
+        let entries = timeline.entries().collect::<alloc::vec::Vec<_>>();
+        assert_eq!(entries, [(0, 10), (3, 4), (8, 7), (12, 3)]);
+        assert_eq!(timeline.total_duration(), 15);
+    }
+
+    #[test]
+    fn test_coverage_gaps() {
+        let gapped = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(2, static_animation(8))
+            .add_animation(15, static_animation(5))
+            .add_animation(16, static_animation(2))
+            .finish();
+        assert_eq!(gapped.coverage_gaps(), [0..2, 10..15]);
+
+        let covered = DynTimelineBuilder::<TestStrip>::new()
+            .add_animation(0, static_animation(10))
+            .add_animation(3, static_animation(2))
+            .add_animation(10, static_animation(5))
+            .finish();
+        assert!(covered.coverage_gaps().is_empty());
+    }
+
+    #[test]
+    fn test_select_timeline_switches_after_finish() {
+        let selected = Rc::new(Cell::new(0));
+        let timeline = SelectTimeline::<TestStrip>::new(selected.clone())
+            .add_animation(static_animation(3))
+            .add_animation(static_animation(5));
+        let active_duration = |tick| {
+            timeline
+                .get_current_entries(tick)
+                .next()
+                .map(|e| e.duration())
+        };
+
+        assert_eq!(active_duration(1), Some(3));
+
+        selected.set(1);
+        assert_eq!(active_duration(2), Some(3));
+        assert!(!timeline.has_finished(3));
+        assert_eq!(active_duration(3), Some(3));
+
+        assert!(timeline.has_finished(4));
+        assert!(timeline.should_repeat());
+        assert_eq!(active_duration(1), Some(5));
+
+        selected.set(7);
+        assert!(timeline.has_finished(6));
+        assert_eq!(active_duration(1), Some(5));
+    }
+
+    #[test]
+    fn test_boxed_timed_animations_start_and_duration() {
+        let animations: alloc::vec::Vec<alloc::boxed::Box<dyn TimedAnimationAt<TestStrip>>> = alloc::vec![
+            alloc::boxed::Box::new(TimedAnimation::new(4, static_animation(10))),
+            alloc::boxed::Box::new(TimedAnimation::new(
+                0,
+                alloc::boxed::Box::new(static_animation(3)) as BoxedAnimation<TestStrip>,
+            )),
+        ];
+
+        let timings = animations
+            .iter()
+            .map(|a| (a.at_tick(), a.duration(), a.max_tick()))
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(timings, [(4, 10, 14), (0, 3, 3)]);
+    }
 }
@@ -0,0 +1,89 @@
+use crate::{indexing::LedId, timeline::Ticks};
+
+/// Duration in real world time, converted to ticks by the tick rate of the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Duration {
+    millis: u32,
+}
+
+impl Duration {
+    pub const fn from_millis(millis: u32) -> Self {
+        Self { millis }
+    }
+
+    /// Durations exceeding the millisecond range are clamped to the maximum.
+    pub const fn from_secs(secs: u32) -> Self {
+        Self {
+            millis: secs.saturating_mul(1000),
+        }
+    }
+
+    pub const fn as_millis(&self) -> u32 {
+        self.millis
+    }
+
+    /// Converts the duration to ticks at the given tick rate, rounded to the nearest tick.
+    /// Durations exceeding the tick range are clamped to the maximum.
+    pub fn to_ticks(&self, tick_hz: u32) -> Ticks {
+        let ticks = (u64::from(self.millis) * u64::from(tick_hz) + 500) / 1000;
+        Ticks::try_from(ticks).unwrap_or(Ticks::MAX)
+    }
+}
+
+/// Length in real world units, converted to an amount of leds by the led density of the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Length {
+    millimeters: u32,
+}
+
+impl Length {
+    pub const fn from_mm(millimeters: u32) -> Self {
+        Self { millimeters }
+    }
+
+    /// Lengths exceeding the millimeter range are clamped to the maximum.
+    pub const fn from_cm(centimeters: u32) -> Self {
+        Self {
+            millimeters: centimeters.saturating_mul(10),
+        }
+    }
+
+    /// Lengths exceeding the millimeter range are clamped to the maximum.
+    pub const fn from_m(meters: u32) -> Self {
+        Self {
+            millimeters: meters.saturating_mul(1000),
+        }
+    }
+
+    /// Converts the length to an amount of leds at the given density, rounded to the nearest led.
+    /// Lengths exceeding the addressable leds are clamped to the maximum.
+    pub fn to_leds(&self, leds_per_meter: u32) -> LedId {
+        let leds = (u64::from(self.millimeters) * u64::from(leds_per_meter) + 500) / 1000;
+        LedId::try_from(leds).unwrap_or(LedId::MAX)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_duration_to_ticks() {
+        assert_eq!(Duration::from_millis(1000).to_ticks(50), 50);
+        assert_eq!(Duration::from_secs(2).to_ticks(60), 120);
+        assert_eq!(Duration::from_millis(25).to_ticks(60), 2);
+        assert_eq!(Duration::from_millis(0).to_ticks(50), 0);
+        assert_eq!(Duration::from_millis(u32::MAX).to_ticks(2000), Ticks::MAX);
+        assert_eq!(Duration::from_secs(u32::MAX).as_millis(), u32::MAX);
+    }
+
+    #[test]
+    fn test_length_to_leds() {
+        assert_eq!(Length::from_cm(30).to_leds(60), 18);
+        assert_eq!(Length::from_m(1).to_leds(144), 144);
+        assert_eq!(Length::from_mm(25).to_leds(30), 1);
+        assert_eq!(Length::from_m(1000).to_leds(144), LedId::MAX);
+        assert_eq!(Length::from_m(u32::MAX), Length::from_mm(u32::MAX));
+        assert_eq!(Length::from_cm(u32::MAX), Length::from_mm(u32::MAX));
+    }
+}
@@ -9,6 +9,116 @@ where
     output_start + ((output_end - output_start) / (input_end - input_start)) * (input - input_start)
 }
 
+/// Numbers [range_map_clamped] can map, implemented for the integer types up to 32 bit and
+/// `f32`.
+pub trait ClampedRangeMap: Copy {
+    fn range_map_clamped(
+        input: Self,
+        input_start: Self,
+        input_end: Self,
+        output_start: Self,
+        output_end: Self,
+    ) -> Self;
+}
+
+/// [range_map] with the input clamped to the input range first, so the result never leaves the
+/// output range, e.g. for transparencies. Both ranges may be descending, an empty input range
+/// maps to `output_start`.
+///
+/// Integers are mapped in `i128`, where the product of two 32 bit ranges can't overflow, and
+/// the result is truncated.
+pub fn range_map_clamped<T: ClampedRangeMap>(
+    input: T,
+    input_start: T,
+    input_end: T,
+    output_start: T,
+    output_end: T,
+) -> T {
+    T::range_map_clamped(input, input_start, input_end, output_start, output_end)
+}
+
+fn range_map_clamped_integer<T>(
+    input: T,
+    input_start: T,
+    input_end: T,
+    output_start: T,
+    output_end: T,
+) -> T
+where
+    T: Into<i64> + TryFrom<i64> + Copy,
+{
+    let (input, input_start, input_end) = (
+        i128::from(Into::<i64>::into(input)),
+        i128::from(Into::<i64>::into(input_start)),
+        i128::from(Into::<i64>::into(input_end)),
+    );
+    let (out_start, out_end) = (
+        i128::from(Into::<i64>::into(output_start)),
+        i128::from(Into::<i64>::into(output_end)),
+    );
+
+    if input_start == input_end {
+        return output_start;
+    }
+
+    let input = input.clamp(input_start.min(input_end), input_start.max(input_end));
+    let mapped =
+        out_start + (out_end - out_start) * (input - input_start) / (input_end - input_start);
+    let mapped = mapped.clamp(out_start.min(out_end), out_start.max(out_end));
+
+    // mapped lies between output_start and output_end, so it always fits into T
+    T::try_from(mapped as i64).unwrap_or(output_start)
+}
+
+macro_rules! impl_clamped_range_map_for_integers {
+    ($($t:ty),*) => {
+        $(
+            impl ClampedRangeMap for $t {
+                fn range_map_clamped(
+                    input: Self,
+                    input_start: Self,
+                    input_end: Self,
+                    output_start: Self,
+                    output_end: Self,
+                ) -> Self {
+                    range_map_clamped_integer(
+                        input,
+                        input_start,
+                        input_end,
+                        output_start,
+                        output_end,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_clamped_range_map_for_integers!(u8, u16, u32, i8, i16, i32);
+
+impl ClampedRangeMap for f32 {
+    fn range_map_clamped(
+        input: Self,
+        input_start: Self,
+        input_end: Self,
+        output_start: Self,
+        output_end: Self,
+    ) -> Self {
+        if input_start == input_end {
+            return output_start;
+        }
+        let input = input.clamp(input_start.min(input_end), input_start.max(input_end));
+        range_map(input, input_start, input_end, output_start, output_end)
+    }
+}
+
+/// Converts a length to `u16`, lengths which don't fit saturate at `u16::MAX`.
+///
+/// Led ids are `u16`, so longer ranges can't be addressed anyway.
+pub(crate) fn saturating_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
 pub fn range_map_to_0_1(input: f32, input_start: f32, input_end: f32) -> f32 {
     range_map(input, input_start, input_end, 0f32, 1f32)
 }
@@ -17,7 +127,7 @@ pub fn range_map_from_0_1(input: f32, output_start: f32, output_end: f32) -> f32
     range_map(input, 0f32, 1f32, output_start, output_end)
 }
 
-pub fn max_3(a: f32, b: f32, c: f32) -> f32 {
+pub(crate) fn max_3(a: f32, b: f32, c: f32) -> f32 {
     if a > b && a > c {
         a
     } else if c > a && c > b {
@@ -27,7 +137,7 @@ pub fn max_3(a: f32, b: f32, c: f32) -> f32 {
     }
 }
 
-pub fn min_3(a: f32, b: f32, c: f32) -> f32 {
+pub(crate) fn min_3(a: f32, b: f32, c: f32) -> f32 {
     if a <= b && a <= c {
         a
     } else if c <= a && c <= b {
@@ -37,10 +147,86 @@ pub fn min_3(a: f32, b: f32, c: f32) -> f32 {
     }
 }
 
-pub fn wrap_on<T: num_traits::Unsigned + Ord>(value: T, max: T) -> T {
+pub(crate) fn wrap_on<T: num_traits::Unsigned + Ord>(value: T, max: T) -> T {
     if value > max {
         value - max
     } else {
         value
     }
 }
+
+/// Clamps a spectrum percentage to `0.0..=1.0`, NaN (e.g. from `0 / 0`) becomes `0.0`.
+///
+/// Every percentage computed from led positions should pass through here before it is used
+/// to sample a spectrum.
+pub(crate) fn clamp_percentage(percentage: f32) -> f32 {
+    if percentage.is_nan() {
+        0.0
+    } else {
+        percentage.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_clamp_percentage() {
+        assert_eq!(clamp_percentage(0.25), 0.25);
+        assert_eq!(clamp_percentage(1.0), 1.0);
+        assert_eq!(clamp_percentage(1.5), 1.0);
+        assert_eq!(clamp_percentage(-0.1), 0.0);
+        assert_eq!(clamp_percentage(f32::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_saturating_u16() {
+        assert_eq!(saturating_u16(300), 300);
+        assert_eq!(saturating_u16(65_535), u16::MAX);
+        assert_eq!(saturating_u16(100_000), u16::MAX);
+    }
+
+    #[test]
+    fn test_range_map_clamped_u8() {
+        assert_eq!(range_map_clamped(200u8, 0, 255, 0, 100), 78);
+        assert_eq!(range_map_clamped(255u8, 0, 255, 0, 100), 100);
+        assert_eq!(range_map_clamped(0u8, 0, 10, 200, 100), 200);
+        assert_eq!(range_map_clamped(10u8, 0, 10, 200, 100), 100);
+        assert_eq!(range_map_clamped(5u8, 10, 0, 0, 100), 50);
+    }
+
+    #[test]
+    fn test_range_map_clamped_clamps() {
+        assert_eq!(range_map_clamped(20u8, 0, 10, 0, 200), 200);
+        assert_eq!(range_map_clamped(-5i16, 0, 10, 0, 100), 0);
+        assert_eq!(range_map_clamped(3u16, 5, 5, 7, 100), 7);
+    }
+
+    #[test]
+    fn test_range_map_clamped_float() {
+        assert_eq!(range_map(1.5, 0.0, 1.0, 0.0, 10.0), 15.0);
+        assert_eq!(range_map_clamped(1.5f32, 0.0, 1.0, 0.0, 10.0), 10.0);
+        assert_eq!(range_map(-0.5, 0.0, 1.0, 0.0, 10.0), -5.0);
+        assert_eq!(range_map_clamped(-0.5f32, 0.0, 1.0, 0.0, 10.0), 0.0);
+
+        assert_eq!(range_map_clamped(0.25f32, 0.0, 1.0, 0.0, 10.0), 2.5);
+        assert_eq!(range_map(0.25, 0.0, 1.0, 0.0, 10.0), 2.5);
+
+        assert_eq!(range_map_clamped(2.0f32, 1.0, 0.0, 0.0, 10.0), 0.0);
+        assert_eq!(range_map_clamped(0.5f32, 1.0, 1.0, 3.0, 10.0), 3.0);
+    }
+
+    #[test]
+    fn test_range_map_clamped_no_overflow() {
+        assert_eq!(
+            range_map_clamped(i32::MAX, 0, i32::MAX, 0, i32::MAX),
+            i32::MAX
+        );
+        assert_eq!(
+            range_map_clamped(u32::MAX / 2, 0, u32::MAX, u32::MAX, 0),
+            u32::MAX / 2 + 1
+        );
+        assert_eq!(range_map_clamped(60_000u16, 0, 65_535, 0, 65_535), 60_000);
+    }
+}